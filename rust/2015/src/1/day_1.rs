@@ -1,26 +1,43 @@
-use std::fs;
+use aoc_common::ParseError;
 
-fn to_levels(s: &str) -> impl Iterator<Item = i32> + '_ {
-    s.chars().scan(0, |state, c| {
-        *state += match c {
-            '(' => Some(1),
-            ')' => Some(-1),
-            _ => None,
-        }?;
+fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    input
+        .trim_end()
+        .char_indices()
+        .map(|(idx, c)| match c {
+            '(' => Ok(1),
+            ')' => Ok(-1),
+            _ => Err(ParseError::at_offset(
+                input,
+                idx,
+                format!("expected `(` or `)`, found {c:?}"),
+            )),
+        })
+        .collect()
+}
+
+fn to_levels(steps: &[i32]) -> impl Iterator<Item = i32> + '_ {
+    steps.iter().scan(0, |state, step| {
+        *state += step;
         Some(*state)
     })
 }
 
-fn main() {
-    let input = fs::read_to_string("./src/1/input.txt").unwrap();
-
-    let part_1_result = to_levels(&input).last().unwrap();
-    println!("Part 1: {}", part_1_result);
+pub fn part_1(input: &str) -> anyhow::Result<i32> {
+    to_levels(&parse(input)?)
+        .last()
+        .ok_or_else(|| anyhow::anyhow!("no instructions"))
+}
 
-    let part_2_result = to_levels(&input)
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    to_levels(&parse(input)?)
         .position(|n| n == -1)
         .map(|x| x + 1)
-        .unwrap();
+        .ok_or_else(|| anyhow::anyhow!("never enters the basement"))
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2015, 1, parse: parse);
 
-    println!("Part 2: {}", part_2_result);
+aoc_common::examples! {
+    "example.txt" => { part_1: -1, part_2: 5 },
 }
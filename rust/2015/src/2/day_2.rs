@@ -1,29 +1,34 @@
+use aoc_common::ParseError;
 use regex::Regex;
-use std::fs;
-
-fn main() {
-    let re = Regex::new(r"(\d+)x(\d+)x(\d+)").unwrap();
-    let input = fs::read_to_string("./src/2/input.txt").unwrap();
-    let values: Vec<(i32, i32, i32)> = re
-        .captures_iter(&input)
-        .map(|cap| {
-            (
-                cap[1].parse::<i32>().unwrap(),
-                cap[2].parse::<i32>().unwrap(),
-                cap[3].parse::<i32>().unwrap(),
-            )
-        })
-        .collect();
 
+fn parse(input: &str) -> Result<Vec<(i32, i32, i32)>, ParseError> {
+    let re = Regex::new(r"^(\d+)x(\d+)x(\d+)$").unwrap();
+    aoc_common::parse_lines(input, |line| {
+        let cap = re
+            .captures(line)
+            .ok_or("expected dimensions like `2x3x4`")?;
+        let dimension = |idx: usize| {
+            cap[idx]
+                .parse::<i32>()
+                .map_err(|_| "dimension is too large")
+        };
+        Ok::<_, &str>((dimension(1)?, dimension(2)?, dimension(3)?))
+    })
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<i32> {
+    let values = parse(input)?;
     let part_1_result = values.iter().fold(0, |acc, (w, l, h)| {
         let a = l * w;
         let b = w * h;
         let c = h * l;
-        return acc + 2 * a + 2 * b + 2 * c + a.min(b).min(c);
+        acc + 2 * a + 2 * b + 2 * c + a.min(b).min(c)
     });
+    Ok(part_1_result)
+}
 
-    println!("part 1 result: {}", part_1_result);
-
+pub fn part_2(input: &str) -> anyhow::Result<i32> {
+    let values = parse(input)?;
     let part_2_result = values.iter().fold(0, |acc, (w, l, h)| {
         let a = w.min(l).min(h);
         let b = match a {
@@ -31,8 +36,37 @@ fn main() {
             _ if a == l => w.min(h),
             _ => w.min(l),
         };
-        return acc + 2 * a + 2 * b + w * l * h;
+        acc + 2 * a + 2 * b + w * l * h
     });
+    Ok(part_2_result)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2015, 2, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 101, part_2: 48 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(
+            boxes in prop::collection::vec((1..1000, 1..1000, 1..1000), 0..20)
+        ) {
+            let input: String = boxes.iter().map(|(l, w, h)| format!("{l}x{w}x{h}\n")).collect();
+            prop_assert_eq!(parse(&input).unwrap(), boxes);
+        }
+    }
 
-    println!("part 2 result: {}", part_2_result);
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("2x3x4\n2x3\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        let err = parse("2x3x99999999999\n").unwrap_err();
+        assert_eq!(err.message, "dimension is too large");
+    }
 }
@@ -1,40 +1,62 @@
+use aoc_common::ParseError;
 use std::collections::HashSet;
-use std::fs;
-
-fn positions(chars: impl Iterator<Item = char>) -> impl Iterator<Item = (i32, i32)> {
-    return chars.scan((0, 0), |state, c| {
-        *state = match c {
-            'v' => Some((state.0, state.1 - 1)),
-            '^' => Some((state.0, state.1 + 1)),
-            '<' => Some((state.0 - 1, state.1)),
-            '>' => Some((state.0 + 1, state.1)),
-            _ => None,
-        }?;
-        Some(*state)
-    });
+
+fn parse(input: &str) -> Result<Vec<(i32, i32)>, ParseError> {
+    input
+        .trim_end()
+        .char_indices()
+        .map(|(idx, c)| match c {
+            'v' => Ok((0, -1)),
+            '^' => Ok((0, 1)),
+            '<' => Ok((-1, 0)),
+            '>' => Ok((1, 0)),
+            _ => Err(ParseError::at_offset(
+                input,
+                idx,
+                format!("expected one of `^v<>`, found {c:?}"),
+            )),
+        })
+        .collect()
 }
 
-fn main() {
-    let input = fs::read_to_string("./src/3/input.txt").unwrap();
+fn positions<'a>(
+    moves: impl Iterator<Item = &'a (i32, i32)> + 'a,
+) -> impl Iterator<Item = (i32, i32)> + 'a {
+    moves.scan((0, 0), |state, (dx, dy)| {
+        *state = (state.0 + dx, state.1 + dy);
+        Some(*state)
+    })
+}
 
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let moves = parse(input)?;
     let mut seen = HashSet::new();
     seen.insert((0, 0));
 
-    for pos in positions(input.chars()) {
+    for pos in positions(moves.iter()) {
         seen.insert(pos);
     }
 
-    println!("part 1: {}", seen.len());
+    Ok(seen.len())
+}
 
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let moves = parse(input)?;
     let mut seen = HashSet::new();
     seen.insert((0, 0));
 
-    for santa_pos in positions(input.chars().step_by(2)) {
+    for santa_pos in positions(moves.iter().step_by(2)) {
         seen.insert(santa_pos);
     }
-    for robot_pos in positions(input.chars().skip(1).step_by(2)) {
+    for robot_pos in positions(moves.iter().skip(1).step_by(2)) {
         seen.insert(robot_pos);
     }
 
-    println!("part 2: {}", seen.len());
+    Ok(seen.len())
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2015, 3, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 2, part_2: 11 },
 }
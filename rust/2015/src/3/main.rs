@@ -0,0 +1,3 @@
+fn main() -> std::process::ExitCode {
+    aoc_common::main(&advent_of_code_2015::day_3::DAY)
+}
@@ -1,19 +1,25 @@
+use anyhow::anyhow;
+use aoc_common::parallel;
 use regex::Regex;
 
-fn search(input: &str, re: Regex) -> usize {
-    (0..)
-        .map(|n| md5::compute(format!("{}{}", input, n)))
-        .map(|digest| format!("{:x}", digest))
-        .position(|s| re.is_match(&s))
-        .unwrap()
+fn search(input: &str, re: Regex) -> anyhow::Result<usize> {
+    parallel::find_first(0..usize::MAX, |n| {
+        let digest = md5::compute(format!("{}{}", input, n));
+        re.is_match(&format!("{:x}", digest))
+    })
+    .ok_or_else(|| anyhow!("no number gives a hash matching {re}"))
 }
 
-fn main() {
-    let input = "iwrupvqb";
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    search(input.trim(), Regex::new(r"^0{5}")?)
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    search(input.trim(), Regex::new(r"^0{6}")?)
+}
 
-    let part_1_result = search(input, Regex::new(r"^0{5}").unwrap());
-    println!("part 1 result: {}", part_1_result);
+pub const DAY: aoc_common::Day = aoc_common::day!(2015, 4);
 
-    let part_2_result = search(input, Regex::new(r"^0{6}").unwrap());
-    println!("part 2 result: {}", part_2_result);
+aoc_common::examples! {
+    "example.txt" => { part_1: 609043 },
 }
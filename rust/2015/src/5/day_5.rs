@@ -1,5 +1,4 @@
 use lazy_static::lazy_static;
-use std::fs;
 
 fn has_duplicated_char(s: &str) -> bool {
     s.chars()
@@ -15,14 +14,14 @@ fn has_three_vowels(s: &str) -> bool {
     lazy_static! {
         static ref VOWELS: regex::Regex = regex::Regex::new(r"[aeiou]").unwrap();
     }
-    VOWELS.find_iter(&s).count() >= 3
+    VOWELS.find_iter(s).count() >= 3
 }
 
 fn has_no_illegal_pairs(s: &str) -> bool {
     lazy_static! {
         static ref ILLEGAL_PAIRS: regex::Regex = regex::Regex::new(r"ab|cd|pq|xy").unwrap();
     }
-    !ILLEGAL_PAIRS.is_match(&s)
+    !ILLEGAL_PAIRS.is_match(s)
 }
 
 fn has_pair_twice(s: &str) -> bool {
@@ -30,7 +29,7 @@ fn has_pair_twice(s: &str) -> bool {
         static ref PAIRS: fancy_regex::Regex =
             fancy_regex::Regex::new(r"([a-z][a-z]).*\1").unwrap();
     }
-    PAIRS.is_match(&s).unwrap()
+    PAIRS.is_match(s).unwrap()
 }
 
 fn has_repeat_with_one_between(s: &str) -> bool {
@@ -38,22 +37,28 @@ fn has_repeat_with_one_between(s: &str) -> bool {
         static ref REPEAT_WITH_ONE_BETWEEN: fancy_regex::Regex =
             fancy_regex::Regex::new(r"([a-z]).\1").unwrap();
     }
-    REPEAT_WITH_ONE_BETWEEN.is_match(&s).unwrap()
+    REPEAT_WITH_ONE_BETWEEN.is_match(s).unwrap()
 }
 
-fn main() {
-    let input = fs::read_to_string("./src/5/input.txt").unwrap();
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
     let part_1_result = input
         .lines()
-        .filter(|s| has_three_vowels(&s) && has_duplicated_char(&s) && has_no_illegal_pairs(&s))
+        .filter(|s| has_three_vowels(s) && has_duplicated_char(s) && has_no_illegal_pairs(s))
         .count();
+    Ok(part_1_result)
+}
 
-    println!("Part 1 result: {}", part_1_result);
-
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let part_2_result = input
         .lines()
-        .filter(|s| has_pair_twice(&s) && has_repeat_with_one_between(&s))
+        .filter(|s| has_pair_twice(s) && has_repeat_with_one_between(s))
         .count();
+    Ok(part_2_result)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2015, 5);
 
-    println!("Part 2 result: {}", part_2_result);
+aoc_common::examples! {
+    "example.txt" => { part_1: 2 },
+    "example_2.txt" => { part_2: 2 },
 }
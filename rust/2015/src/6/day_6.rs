@@ -1,7 +1,12 @@
+use aoc_common::{
+    image::{self, Image, Rgb},
+    ParseError,
+};
 use lazy_static::lazy_static;
 
-type Grid<T> = [[T; 1000]; 1000];
+type Grid<T> = Vec<[T; 1000]>;
 
+#[derive(Debug, PartialEq)]
 struct Rect {
     x1: usize,
     y1: usize,
@@ -9,34 +14,42 @@ struct Rect {
     y2: usize,
 }
 
+#[derive(Debug, PartialEq)]
 enum Instruction {
     TurnOn(Rect),
     TurnOff(Rect),
     Toggle(Rect),
 }
 
-fn parse(s: &str) -> Vec<Instruction> {
+fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
     lazy_static! {
         static ref PATTERN: regex::Regex =
-            regex::Regex::new(r"(toggle|turn off|turn on) (\d+),(\d+) through (\d+),(\d+)")
+            regex::Regex::new(r"^(toggle|turn off|turn on) (\d+),(\d+) through (\d+),(\d+)$")
                 .unwrap();
     }
-    PATTERN
-        .captures_iter(&s)
-        .map(|cap| {
-            let x1 = cap[2].parse::<usize>().unwrap();
-            let y1 = cap[3].parse::<usize>().unwrap();
-            let x2 = cap[4].parse::<usize>().unwrap();
-            let y2 = cap[5].parse::<usize>().unwrap();
-            let rect = Rect { x1, y1, x2, y2 };
-            match &cap[1] {
-                "toggle" => Instruction::Toggle(rect),
-                "turn on" => Instruction::TurnOn(rect),
-                "turn off" => Instruction::TurnOff(rect),
-                _ => panic!(),
-            }
+    aoc_common::parse_lines(input, |line| {
+        let cap = PATTERN
+            .captures(line)
+            .ok_or("expected an instruction like `turn on 0,0 through 999,999`")?;
+        let coord = |idx: usize| match cap[idx].parse::<usize>() {
+            Ok(n) if n < 1000 => Ok(n),
+            _ => Err("coordinates must be between 0 and 999"),
+        };
+        let rect = Rect {
+            x1: coord(2)?,
+            y1: coord(3)?,
+            x2: coord(4)?,
+            y2: coord(5)?,
+        };
+        if rect.x1 > rect.x2 || rect.y1 > rect.y2 {
+            return Err("the first corner must be above and left of the second");
+        }
+        Ok(match &cap[1] {
+            "toggle" => Instruction::Toggle(rect),
+            "turn on" => Instruction::TurnOn(rect),
+            _ => Instruction::TurnOff(rect),
         })
-        .collect()
+    })
 }
 
 fn update_rect<T>(grid: &mut Grid<T>, rect: &Rect, f: fn(&T) -> T) {
@@ -48,38 +61,113 @@ fn update_rect<T>(grid: &mut Grid<T>, rect: &Rect, f: fn(&T) -> T) {
 }
 
 fn fold_lights<Acc, T>(grid: &Grid<T>, initial: Acc, f: fn(Acc, &T) -> Acc) -> Acc {
-    return grid
-        .iter()
-        .fold(initial, |acc, row| row.iter().fold(acc, f));
+    grid.iter()
+        .fold(initial, |acc, row| row.iter().fold(acc, f))
 }
 
-fn main() {
-    let input = std::fs::read_to_string("./src/6/input.txt").unwrap();
-    let instructions: Vec<Instruction> = parse(&input);
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    let instructions: Vec<Instruction> = parse(input)?;
 
-    let mut grid: Grid<bool> = [[false; 1000]; 1000];
+    let mut grid: Grid<bool> = vec![[false; 1000]; 1000];
 
     for instr in instructions.iter() {
         match &instr {
-            Instruction::TurnOn(rect) => update_rect(&mut grid, &rect, |_| true),
-            Instruction::TurnOff(rect) => update_rect(&mut grid, &rect, |_| false),
-            Instruction::Toggle(rect) => update_rect(&mut grid, &rect, |&v| !v),
+            Instruction::TurnOn(rect) => update_rect(&mut grid, rect, |_| true),
+            Instruction::TurnOff(rect) => update_rect(&mut grid, rect, |_| false),
+            Instruction::Toggle(rect) => update_rect(&mut grid, rect, |&v| !v),
         }
     }
+    image::export("2015_day_6_lights", || {
+        Image::from_fn(1000, 1000, |x, y| match grid[y][x] {
+            true => Rgb::WHITE,
+            false => Rgb::BLACK,
+        })
+    })?;
     let part_1_result = fold_lights(&grid, 0, |acc, &v| if v { acc + 1 } else { acc });
-    println!("part 1: {}", part_1_result);
+    Ok(part_1_result)
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    let instructions: Vec<Instruction> = parse(input)?;
 
-    let mut grid = [[0; 1000]; 1000];
+    let mut grid: Grid<u32> = vec![[0; 1000]; 1000];
 
     for instr in instructions.iter() {
         match &instr {
-            Instruction::TurnOn(rect) => update_rect(&mut grid, &rect, |&v| v + 1),
+            Instruction::TurnOn(rect) => update_rect(&mut grid, rect, |&v| v + 1),
             Instruction::TurnOff(rect) => {
-                update_rect(&mut grid, &rect, |&v| if v > 0 { v - 1 } else { 0 })
+                update_rect(&mut grid, rect, |&v| if v > 0 { v - 1 } else { 0 })
             }
-            Instruction::Toggle(rect) => update_rect(&mut grid, &rect, |&v| v + 2),
+            Instruction::Toggle(rect) => update_rect(&mut grid, rect, |&v| v + 2),
         }
     }
+    image::export("2015_day_6_brightness", || {
+        let max = fold_lights(&grid, 0, |acc, &v| acc.max(v));
+        Image::from_fn(1000, 1000, |x, y| Rgb::grey(grid[y][x].into(), max.into()))
+    })?;
     let part_2_result = fold_lights(&grid, 0, |acc, &v| acc + v);
-    println!("part 2: {}", part_2_result);
+    Ok(part_2_result)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2015, 6, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 998996, part_2: 1001996 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn rect() -> impl Strategy<Value = Rect> {
+        (0..1000usize, 0..1000usize, 0..1000usize, 0..1000usize).prop_map(|(a, b, c, d)| Rect {
+            x1: a.min(c),
+            y1: b.min(d),
+            x2: a.max(c),
+            y2: b.max(d),
+        })
+    }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        prop_oneof![
+            rect().prop_map(Instruction::TurnOn),
+            rect().prop_map(Instruction::TurnOff),
+            rect().prop_map(Instruction::Toggle),
+        ]
+    }
+
+    fn render(instruction: &Instruction) -> String {
+        let (action, r) = match instruction {
+            Instruction::TurnOn(r) => ("turn on", r),
+            Instruction::TurnOff(r) => ("turn off", r),
+            Instruction::Toggle(r) => ("toggle", r),
+        };
+        format!("{action} {},{} through {},{}\n", r.x1, r.y1, r.x2, r.y2)
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(instructions in prop::collection::vec(instruction(), 0..20)) {
+            let input: String = instructions.iter().map(render).collect();
+            prop_assert_eq!(parse(&input).unwrap(), instructions);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let message = |input| parse(input).unwrap_err().message;
+        assert_eq!(
+            message("turn on 0,0 through 1000,2"),
+            "coordinates must be between 0 and 999"
+        );
+        assert_eq!(
+            message("toggle 5,5 through 4,6"),
+            "the first corner must be above and left of the second"
+        );
+        assert_eq!(
+            message("turn up 0,0 through 1,1"),
+            "expected an instruction like `turn on 0,0 through 999,999`"
+        );
+    }
 }
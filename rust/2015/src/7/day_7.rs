@@ -1,43 +1,53 @@
-extern crate pest;
-#[macro_use]
-extern crate pest_derive;
-
-use crate::pest::Parser;
+use aoc_common::ParseError;
+use pest::{
+    error::{ErrorVariant, InputLocation},
+    Parser,
+};
+use pest_derive::Parser;
 use std::collections::HashMap;
-use std::fs;
 
 #[derive(Parser)]
 #[grammar = "./7/grammar.pest"] // relative to src
 pub struct BitwiseLogicGateParser;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 enum Value {
     Identifier(String),
     Number(u16),
 }
 
+type PestError = pest::error::Error<Rule>;
+
 impl Value {
-    fn parse(pair: pest::iterators::Pair<Rule>) -> Value {
+    fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Value, PestError> {
         assert!(pair.as_rule() == Rule::value);
         let token = pair.into_inner().next().unwrap();
         match token.as_rule() {
-            Rule::ident => Value::Identifier(token.as_str().to_owned()),
-            Rule::number => Value::Number(token.as_str().parse::<u16>().unwrap()),
+            Rule::ident => Ok(Value::Identifier(token.as_str().to_owned())),
+            Rule::number => match token.as_str().parse::<u16>() {
+                Ok(n) => Ok(Value::Number(n)),
+                Err(_) => Err(PestError::new_from_span(
+                    ErrorVariant::CustomError {
+                        message: "signals are 16 bits, this number is too large".to_owned(),
+                    },
+                    token.as_span(),
+                )),
+            },
             _ => unreachable!(),
         }
     }
 }
 
-impl Into<Expression> for Value {
-    fn into(self) -> Expression {
-        match self {
+impl From<Value> for Expression {
+    fn from(val: Value) -> Self {
+        match val {
             Value::Identifier(i) => Expression::Identifier(i),
             Value::Number(n) => Expression::Number(n),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 enum Expression {
     And(Value, Value),
     Or(Value, Value),
@@ -49,33 +59,33 @@ enum Expression {
 }
 
 impl Expression {
-    fn parse(pair: pest::iterators::Pair<Rule>) -> Expression {
+    fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Expression, PestError> {
         assert!(pair.as_rule() == Rule::expr);
         let inner = pair.into_inner().next().unwrap();
-        return match inner.as_rule() {
+        match inner.as_rule() {
             Rule::unary_expr => {
                 let mut tokens = inner.into_inner();
                 let operator = tokens.next().unwrap().as_str();
-                let value = Value::parse(tokens.next().unwrap());
+                let value = Value::parse(tokens.next().unwrap())?;
                 assert!(operator == "NOT");
-                return Expression::Not(value);
+                Ok(Expression::Not(value))
             }
             Rule::binary_expr => {
                 let mut tokens = inner.into_inner();
-                let left = Value::parse(tokens.next().unwrap());
+                let left = Value::parse(tokens.next().unwrap())?;
                 let operator = tokens.next().unwrap().as_str();
-                let right = Value::parse(tokens.next().unwrap());
-                return match operator {
+                let right = Value::parse(tokens.next().unwrap())?;
+                Ok(match operator {
                     "OR" => Expression::Or(left, right),
                     "AND" => Expression::And(left, right),
                     "LSHIFT" => Expression::LShift(left, right),
                     "RSHIFT" => Expression::RShift(left, right),
                     _ => unreachable!("invalid operator!"),
-                };
+                })
             }
-            Rule::value => Value::parse(inner).into(),
+            Rule::value => Ok(Value::parse(inner)?.into()),
             _ => unreachable!("unexpected expression type"),
-        };
+        }
     }
 }
 
@@ -83,14 +93,14 @@ impl Expression {
 struct Statements(HashMap<String, Expression>);
 
 impl Statements {
-    fn parse(pair: pest::iterators::Pair<Rule>) -> Statements {
+    fn parse(pair: pest::iterators::Pair<Rule>) -> Result<Statements, PestError> {
         let mut statements: HashMap<String, Expression> = HashMap::new();
 
         for statement in pair.into_inner() {
             match statement.as_rule() {
                 Rule::statement => {
                     let mut tokens = statement.into_inner();
-                    let expr = Expression::parse(tokens.next().unwrap());
+                    let expr = Expression::parse(tokens.next().unwrap())?;
                     let identifier = tokens.next().unwrap().as_str().to_owned();
                     statements.insert(identifier, expr);
                 }
@@ -99,18 +109,18 @@ impl Statements {
             }
         }
 
-        return Statements(statements);
+        Ok(Statements(statements))
     }
 
     fn eval_id(&self, id: &str, cache: &mut HashMap<String, u16>) -> u16 {
         let Statements(rules) = self;
         let expr = rules.get(id).unwrap().to_owned();
-        return self.eval(expr, cache);
+        self.eval(expr, cache)
     }
 
     fn eval(&self, expr: Expression, cache: &mut HashMap<String, u16>) -> u16 {
         match expr {
-            Expression::Number(x) => return x,
+            Expression::Number(x) => x,
             Expression::Identifier(id) => match cache.get(&id) {
                 Some(x) => *x,
                 None => {
@@ -138,20 +148,106 @@ impl Statements {
     }
 }
 
-fn main() {
-    let unparsed_input = fs::read_to_string("./src/7/input.txt").expect("cannot read input");
-    let input = BitwiseLogicGateParser::parse(Rule::file, &unparsed_input)
-        .expect("parse error")
-        .next()
-        .unwrap();
+fn parse(input: &str) -> Result<Statements, ParseError> {
+    BitwiseLogicGateParser::parse(Rule::file, input)
+        .and_then(|mut pairs| Statements::parse(pairs.next().unwrap()))
+        .map_err(|err| {
+            let offset = match err.location {
+                InputLocation::Pos(offset) | InputLocation::Span((offset, _)) => offset,
+            };
+            ParseError::at_offset(input, offset, err.variant.message())
+        })
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<u16> {
+    let statements = parse(input)?;
+    let mut cache: HashMap<String, u16> = HashMap::new();
+    Ok(statements.eval_id("a", &mut cache))
+}
 
-    let statements = Statements::parse(input);
+pub fn part_2(input: &str) -> anyhow::Result<u16> {
+    let statements = parse(input)?;
     let mut cache: HashMap<String, u16> = HashMap::new();
     let result = statements.eval_id("a", &mut cache);
-    println!("result part 1: {}", result);
 
     let mut cache: HashMap<String, u16> = HashMap::new();
     cache.insert("b".to_owned(), result);
-    let result = statements.eval_id("a", &mut cache);
-    println!("result part 2: {}", result);
+    Ok(statements.eval_id("a", &mut cache))
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2015, 7, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 72 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Uppercase words are the operators
+    const IDENT: &str = "[a-z]{1,2}";
+
+    fn value() -> impl Strategy<Value = Value> {
+        prop_oneof![
+            IDENT.prop_map(Value::Identifier),
+            any::<u16>().prop_map(Value::Number),
+        ]
+    }
+
+    fn expression() -> impl Strategy<Value = Expression> {
+        prop_oneof![
+            (value(), value()).prop_map(|(a, b)| Expression::And(a, b)),
+            (value(), value()).prop_map(|(a, b)| Expression::Or(a, b)),
+            (value(), value()).prop_map(|(a, b)| Expression::LShift(a, b)),
+            (value(), value()).prop_map(|(a, b)| Expression::RShift(a, b)),
+            value().prop_map(Expression::Not),
+            IDENT.prop_map(Expression::Identifier),
+            any::<u16>().prop_map(Expression::Number),
+        ]
+    }
+
+    fn render_value(value: &Value) -> String {
+        match value {
+            Value::Identifier(id) => id.clone(),
+            Value::Number(n) => n.to_string(),
+        }
+    }
+
+    fn render(expression: &Expression) -> String {
+        let binary = |a, op, b| format!("{} {op} {}", render_value(a), render_value(b));
+        match expression {
+            Expression::And(a, b) => binary(a, "AND", b),
+            Expression::Or(a, b) => binary(a, "OR", b),
+            Expression::LShift(a, b) => binary(a, "LSHIFT", b),
+            Expression::RShift(a, b) => binary(a, "RSHIFT", b),
+            Expression::Not(a) => format!("NOT {}", render_value(a)),
+            Expression::Identifier(id) => id.clone(),
+            Expression::Number(n) => n.to_string(),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(
+            statements in prop::collection::hash_map(IDENT, expression(), 0..20)
+        ) {
+            let input: String = statements
+                .iter()
+                .map(|(target, expression)| format!("{} -> {target}\n", render(expression)))
+                .collect();
+            prop_assert_eq!(parse(&input).unwrap().0, statements);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("123 -> x\n65536 -> y\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.message, "signals are 16 bits, this number is too large");
+
+        let err = parse("x AND -> y\n").unwrap_err();
+        assert_eq!(err.line, 1);
+    }
 }
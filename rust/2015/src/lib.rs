@@ -0,0 +1,30 @@
+#[path = "1/day_1.rs"]
+pub mod day_1;
+
+#[path = "2/day_2.rs"]
+pub mod day_2;
+
+#[path = "3/day_3.rs"]
+pub mod day_3;
+
+#[path = "4/day_4.rs"]
+pub mod day_4;
+
+#[path = "5/day_5.rs"]
+pub mod day_5;
+
+#[path = "6/day_6.rs"]
+pub mod day_6;
+
+#[path = "7/day_7.rs"]
+pub mod day_7;
+
+pub const DAYS: &[aoc_common::Day] = &[
+    day_1::DAY,
+    day_2::DAY,
+    day_3::DAY,
+    day_4::DAY,
+    day_5::DAY,
+    day_6::DAY,
+    day_7::DAY,
+];
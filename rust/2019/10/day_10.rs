@@ -1,21 +1,6 @@
-use itertools::Itertools;
-use std::{
-    collections::HashSet,
-    io::{stdin, Read},
-    str::FromStr,
-};
-
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
-struct Pos {
-    x: i32,
-    y: i32,
-}
-
-impl Pos {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-}
+use anyhow::bail;
+use aoc_grid::{Grid, Pos};
+use std::{collections::HashSet, str::FromStr};
 
 struct AstroidField {
     grid: HashSet<Pos>,
@@ -25,14 +10,10 @@ impl FromStr for AstroidField {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let grid: HashSet<Pos> = s
-            .split_whitespace()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars().enumerate().filter_map(move |(x, c)| match c {
-                    '#' => Some(Pos::new(x as i32, y as i32)),
-                    _ => None,
-                })
-            })
+            .parse::<Grid<char>>()
+            .map_err(|err| err.locate(s))?
+            .iter()
+            .filter_map(|(pos, c)| (*c == '#').then_some(pos))
             .collect();
         Ok(Self { grid })
     }
@@ -64,24 +45,43 @@ impl AstroidField {
             .filter(move |p| **p != src && !self.is_obscured(src, **p))
     }
 
-    fn find_best(&self) -> (usize, Pos) {
-        self.grid
+    fn find_best(&self) -> anyhow::Result<(usize, Pos)> {
+        let Some(best) = self
+            .grid
             .iter()
             .map(|src| (self.detected(*src).count(), *src))
             .max()
-            .unwrap()
+        else {
+            bail!("no asteroids on the map");
+        };
+        Ok(best)
     }
 }
 
-fn main() -> anyhow::Result<()> {
-    let astroid_field = {
-        let mut s = String::new();
-        stdin().read_to_string(&mut s)?;
-        AstroidField::from_str(&s)?
-    };
-    println!("Part 1: {:?}", astroid_field.find_best().0);
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let astroid_field = AstroidField::from_str(input)?;
+    Ok(astroid_field.find_best()?.0)
+}
+
+pub const DAY: aoc_common::Day =
+    aoc_common::day!(2019, 10, part_1 only, parse: AstroidField::from_str);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 8 },
+    "example_2.txt" => { part_1: 33 },
+    "example_3.txt" => { part_1: 35 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
+    #[test]
+    fn test_no_asteroids() {
+        let err = part_1("...\n...\n").unwrap_err();
+        assert_eq!(err.to_string(), "no asteroids on the map");
+        assert!(part_1("#..\n...\n").is_ok_and(|n| n == 0));
+    }
 }
 
 /*
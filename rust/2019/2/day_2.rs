@@ -0,0 +1,35 @@
+use aoc_intcode::Machine;
+
+/// Runs the gravity assist program with `noun` and `verb` in addresses 1 and 2, returning what it
+/// leaves in address 0.
+fn run(program: &[i64], noun: i64, verb: i64) -> anyhow::Result<i64> {
+    let mut machine = Machine::new(program);
+    machine.memory_mut().set(1, noun)?;
+    machine.memory_mut().set(2, verb)?;
+    machine.run()?;
+    machine.memory().get(0)
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<i64> {
+    let program = aoc_intcode::parse(input)?;
+    run(&program, 12, 2)
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<i64> {
+    let program = aoc_intcode::parse(input)?;
+    for noun in 0..=99 {
+        for verb in 0..=99 {
+            // Not every noun and verb makes for a valid program
+            if run(&program, noun, verb).is_ok_and(|output| output == 19690720) {
+                return Ok(100 * noun + verb);
+            }
+        }
+    }
+    Err(anyhow::anyhow!("no noun and verb produce 19690720"))
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2019, 2, parse: aoc_intcode::parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 100 },
+}
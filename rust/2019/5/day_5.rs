@@ -0,0 +1,29 @@
+use anyhow::{anyhow, bail};
+use aoc_intcode::Machine;
+
+/// Runs the diagnostic program for the system with ID `system`, returning the diagnostic code.
+fn diagnose(input: &str, system: i64) -> anyhow::Result<i64> {
+    let program = aoc_intcode::parse(input)?;
+    let outputs = Machine::new(&program).with_inputs([system]).run()?;
+    let (code, tests) = outputs
+        .split_last()
+        .ok_or_else(|| anyhow!("the diagnostic program output nothing"))?;
+    if let Some(n) = tests.iter().position(|output| *output != 0) {
+        bail!("diagnostic test {} failed with {}", n + 1, tests[n]);
+    }
+    Ok(*code)
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<i64> {
+    diagnose(input, 1)
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<i64> {
+    diagnose(input, 5)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2019, 5, parse: aoc_intcode::parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_2: 999 },
+}
@@ -0,0 +1,46 @@
+use anyhow::anyhow;
+use aoc_intcode::{Machine, Pipeline};
+use itertools::Itertools;
+
+/// Runs the amplifiers in a chain with each of the orders of `phases`, returning the highest
+/// signal that comes out of the last one.
+fn max_signal(
+    input: &str,
+    phases: [i64; 5],
+    run: impl Fn(Pipeline) -> anyhow::Result<Vec<i64>>,
+) -> anyhow::Result<i64> {
+    let program = aoc_intcode::parse(input)?;
+    let mut max = None;
+    for order in phases.into_iter().permutations(phases.len()) {
+        let amplifiers = order
+            .into_iter()
+            .map(|phase| Machine::new(&program).with_inputs([phase]));
+        let pipeline = Pipeline::new(amplifiers);
+        pipeline.input.send(0)?;
+        let signal = run(pipeline)?
+            .last()
+            .copied()
+            .ok_or_else(|| anyhow!("the amplifiers output no signal"))?;
+        max = max.max(Some(signal));
+    }
+    Ok(max.unwrap())
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<i64> {
+    max_signal(input, [0, 1, 2, 3, 4], |pipeline| {
+        let signal = pipeline.output.recv();
+        pipeline.join()?;
+        Ok(signal.into_iter().collect())
+    })
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<i64> {
+    max_signal(input, [5, 6, 7, 8, 9], Pipeline::feedback)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2019, 7, parse: aoc_intcode::parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 43210 },
+    "example_2.txt" => { part_2: 139629729 },
+}
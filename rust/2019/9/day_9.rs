@@ -0,0 +1,26 @@
+use anyhow::bail;
+use aoc_intcode::Machine;
+
+/// Runs the BOOST program in `mode`, which outputs just one value unless it finds opcodes that
+/// don't work.
+fn boost(input: &str, mode: i64) -> anyhow::Result<i64> {
+    let program = aoc_intcode::parse(input)?;
+    match Machine::new(&program).with_inputs([mode]).run()?[..] {
+        [value] => Ok(value),
+        ref outputs => bail!("BOOST reports malfunctioning opcodes: {outputs:?}"),
+    }
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<i64> {
+    boost(input, 1)
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<i64> {
+    boost(input, 2)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2019, 9, parse: aoc_intcode::parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 1125899906842624u64, part_2: 1125899906842624u64 },
+}
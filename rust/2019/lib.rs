@@ -0,0 +1,16 @@
+#[path = "2/day_2.rs"]
+pub mod day_2;
+
+#[path = "5/day_5.rs"]
+pub mod day_5;
+
+#[path = "7/day_7.rs"]
+pub mod day_7;
+
+#[path = "9/day_9.rs"]
+pub mod day_9;
+
+#[path = "10/day_10.rs"]
+pub mod day_10;
+
+pub const DAYS: &[aoc_common::Day] = &[day_2::DAY, day_5::DAY, day_7::DAY, day_9::DAY, day_10::DAY];
@@ -1,24 +1,31 @@
 use itertools::Itertools;
-use std::{io, io::prelude::*};
 
-fn main() {
-    let input: Vec<u32> = io::stdin()
-        .lock()
-        .lines()
-        .map(|x| x.unwrap().parse::<u32>().unwrap())
-        .collect();
+fn parse(input: &str) -> Result<Vec<u32>, aoc_common::ParseError> {
+    aoc_common::parse_lines(input, str::parse::<u32>)
+}
 
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let input = parse(input)?;
     let part_1_result = input
         .iter()
         .tuple_windows()
         .filter(|(&a, &b)| b > a)
         .count();
-    println!("Part 1: {}", part_1_result);
+    Ok(part_1_result)
+}
 
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let input = parse(input)?;
     let part_2_result = input
         .iter()
         .tuple_windows()
         .filter(|(&a, &b, &c, &d)| (b + c + d) > (a + b + c))
         .count();
-    println!("Part 2: {}", part_2_result)
+    Ok(part_2_result)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2021, 1, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 7, part_2: 5 },
 }
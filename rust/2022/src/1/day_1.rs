@@ -1,18 +1,57 @@
-use std::{io, io::prelude::*};
-
-fn main() {
-    let mut lst = io::stdin().lock().lines().fold(vec![0], |mut acc, line| {
-        match line.unwrap().parse::<u32>() {
-            Ok(n) => *acc.last_mut().unwrap() += n,
-            Err(_) => acc.push(0),
+fn sorted_calories(input: &str) -> Result<Vec<u32>, aoc_common::ParseError> {
+    let mut lst: Vec<u32> = vec![0];
+    for line in input.lines() {
+        match line.trim() {
+            "" => lst.push(0),
+            s => {
+                let n = s.parse::<u32>().map_err(|_| {
+                    aoc_common::ParseError::at(input, line, "expected calories or a blank line")
+                })?;
+                let total = lst.last_mut().unwrap();
+                *total = total.checked_add(n).ok_or_else(|| {
+                    aoc_common::ParseError::at(input, line, "too many calories for one elf")
+                })?;
+            }
         }
-        acc
-    });
+    }
     lst.sort();
+    Ok(lst)
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    let lst = sorted_calories(input)?;
+    Ok(*lst.last().unwrap())
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    let lst = sorted_calories(input)?;
+    Ok(lst.iter().rev().take(3).sum::<u32>())
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2022, 1, parse: sorted_calories);
 
-    let part_1_result = lst.last().unwrap();
-    println!("part 1: {part_1_result}");
+aoc_common::examples! {
+    "example.txt" => { part_1: 24000, part_2: 45000 },
+}
 
-    let part_2_result = lst.iter().rev().take(3).sum::<u32>();
-    println!("part 2: {part_2_result}");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(
+            elves in prop::collection::vec(prop::collection::vec(0..100_000u32, 1..6), 1..10),
+        ) {
+            let input = elves
+                .iter()
+                .map(|items| items.iter().map(|n| format!("{n}\n")).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let mut totals: Vec<u32> = elves.iter().map(|items| items.iter().sum()).collect();
+            totals.sort();
+            prop_assert_eq!(sorted_calories(&input).unwrap(), totals);
+        }
+    }
 }
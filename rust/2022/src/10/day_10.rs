@@ -1,18 +1,29 @@
+use aoc_common::{
+    image::{self, Image, Rgb},
+    Param,
+};
 use std::str::FromStr;
 
-#[derive(Debug)]
-enum Error {
-    ParseError(String),
-}
+const SAMPLE_CYCLES: Param = Param {
+    name: "sample-cycles",
+    default: "20,60,100,140,180,220",
+    help: "The cycles to sum the signal strengths of",
+};
+
+const CRT_WIDTH: Param = Param {
+    name: "crt-width",
+    default: "40",
+    help: "How many pixels wide the CRT is",
+};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 enum Instruction {
     NoOp,
     AddX(i32),
 }
 
 impl FromStr for Instruction {
-    type Err = Error;
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut iter = s.splitn(2, " ").map(|s| s.trim());
@@ -21,40 +32,37 @@ impl FromStr for Instruction {
             Some("addx") => {
                 let n = iter
                     .next()
-                    .ok_or(Error::ParseError(String::from(
-                        "expected integer, got <None>",
-                    )))?
+                    .ok_or("expected an integer after `addx`")?
                     .parse::<i32>()
-                    .map_err(|e| Error::ParseError(e.to_string()))?;
+                    .map_err(|e| format!("invalid integer after `addx`: {e}"))?;
                 Ok(Instruction::AddX(n))
             }
-            Some(s) => Err(Error::ParseError(format!("Invalid insruction {s:?}"))),
-            None => Err(Error::ParseError(String::from(
-                "invalid instruction <None>",
-            ))),
+            Some(s) => Err(format!("invalid instruction {s:?}")),
+            None => Err("expected an instruction".to_owned()),
         }
     }
 }
 
-fn main() {
-    let input = std::io::stdin()
-        .lines()
-        .map(|s| s.unwrap().parse())
-        .collect::<Result<Vec<Instruction>, Error>>()
-        .unwrap();
+fn parse(input: &str) -> anyhow::Result<Vec<Instruction>> {
+    let input = aoc_common::parse_lines(input, str::parse::<Instruction>)?;
 
-    let instructions: Vec<Instruction> = input
+    Ok(input
         .iter()
         .flat_map(|i| match i {
             Instruction::NoOp => vec![Instruction::NoOp],
             Instruction::AddX(n) => vec![Instruction::NoOp, Instruction::AddX(*n)],
         })
-        .collect();
+        .collect())
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<i32> {
+    let instructions = parse(input)?;
+    let sample_cycles: Vec<usize> = SAMPLE_CYCLES.list()?;
 
     let mut register = 1;
     let mut sum = 0;
     for (cycle, instruction) in instructions.iter().enumerate() {
-        if [20, 60, 100, 140, 180, 220].contains(&(cycle + 1)) {
+        if sample_cycles.contains(&(cycle + 1)) {
             sum += register * ((cycle + 1) as i32);
         }
         match instruction {
@@ -65,18 +73,28 @@ fn main() {
         }
     }
 
-    println!("\npart 1: {sum}");
+    Ok(sum)
+}
+
+/// Renders the CRT image
+pub fn part_2(input: &str) -> anyhow::Result<String> {
+    let instructions = parse(input)?;
+    let width: usize = CRT_WIDTH.value()?;
+    if width == 0 {
+        anyhow::bail!("the CRT is at least one pixel wide");
+    }
 
-    register = 1;
+    let mut image = String::new();
+    let mut register = 1;
     for (cycle, instruction) in instructions.iter().enumerate() {
-        let pos: i32 = ((cycle) % 40) as i32;
+        let pos: i32 = ((cycle) % width) as i32;
         if register >= pos - 1 && register <= pos + 1 {
-            print!("#");
+            image.push('#');
         } else {
-            print!(".");
+            image.push('.');
         }
-        if pos == 39 {
-            print!("\n");
+        if pos as usize == width - 1 {
+            image.push('\n');
         }
         match instruction {
             Instruction::NoOp => (),
@@ -85,4 +103,59 @@ fn main() {
             }
         }
     }
+
+    let image = image.trim_end().to_owned();
+    image::export("2022_day_10_crt", || {
+        let rows: Vec<&[u8]> = image.lines().map(str::as_bytes).collect();
+        Image::from_fn(width, rows.len(), |x, y| match rows[y].get(x) {
+            Some(b'#') => Rgb(120, 255, 120),
+            _ => Rgb::BLACK,
+        })
+        .scaled(10)
+    })?;
+    Ok(image)
+}
+
+pub const DAY: aoc_common::Day =
+    aoc_common::day!(2022, 10, parse: parse, params: [SAMPLE_CYCLES, CRT_WIDTH]);
+
+aoc_common::examples! {
+    "example.txt" => {
+        part_1: 13140,
+        part_2: "\
+            ##..##..##..##..##..##..##..##..##..##..\n\
+            ###...###...###...###...###...###...###.\n\
+            ####....####....####....####....####....\n\
+            #####.....#####.....#####.....#####.....\n\
+            ######......######......######......####\n\
+            #######.......#######.......#######.....",
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        prop_oneof![
+            Just(Instruction::NoOp),
+            any::<i32>().prop_map(Instruction::AddX)
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(instructions in prop::collection::vec(instruction(), 0..20)) {
+            let input: String = instructions
+                .iter()
+                .map(|i| match i {
+                    Instruction::NoOp => "noop\n".to_string(),
+                    Instruction::AddX(n) => format!("addx {n}\n"),
+                })
+                .collect();
+            let parsed = aoc_common::parse_lines(&input, str::parse::<Instruction>).unwrap();
+            prop_assert_eq!(parsed, instructions);
+        }
+    }
 }
@@ -1,13 +1,32 @@
+use aoc_common::Param;
 use std::collections::VecDeque;
 
-#[derive(Debug, Clone)]
+const PART_1_ROUNDS: Param = Param {
+    name: "part-1-rounds",
+    default: "20",
+    help: "How many rounds to play in part 1",
+};
+
+const PART_2_ROUNDS: Param = Param {
+    name: "part-2-rounds",
+    default: "10000",
+    help: "How many rounds to play in part 2",
+};
+
+const RELIEF_FACTOR: Param = Param {
+    name: "relief-factor",
+    default: "3",
+    help: "What worry levels are divided by after an inspection in part 1",
+};
+
+#[derive(Debug, Clone, PartialEq)]
 enum Operation {
     Add(usize),
     Mul(usize),
     Square,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Monkey {
     items: VecDeque<usize>,
     operation: Operation,
@@ -18,6 +37,7 @@ pub struct Monkey {
 }
 
 mod parser {
+    use aoc_common::ParseError;
     use std::collections::VecDeque;
 
     use nom::branch::alt;
@@ -26,7 +46,7 @@ mod parser {
     use nom::combinator::{map, map_res};
     use nom::multi::{many0, separated_list1};
     use nom::sequence::{delimited, preceded, terminated};
-    use nom::IResult;
+    use nom::{Finish, IResult};
 
     fn number(input: &str) -> IResult<&str, usize> {
         map_res(digit1, |s: &str| s.parse::<usize>())(input)
@@ -68,7 +88,7 @@ mod parser {
             multispace0,
         )(s)?;
 
-        return Ok((
+        Ok((
             s,
             super::Monkey {
                 items,
@@ -78,21 +98,22 @@ mod parser {
                 if_false_throw_to,
                 activity: 0,
             },
-        ));
-    }
-
-    #[derive(Debug)]
-    pub enum ParseError<'a> {
-        Unparsed(&'a str),
-        Error(nom::Err<nom::error::Error<&'a str>>),
+        ))
     }
 
     pub fn parse_monkeys(input: &str) -> Result<Vec<super::Monkey>, ParseError> {
-        match many0(monkey)(input) {
-            Ok(("", monkeys)) => Ok(monkeys),
-            Ok((rest, _)) => Err(ParseError::Unparsed(rest)),
-            Err(err) => Err(ParseError::Error(err)),
-        }
+        let rest = match many0(monkey)(input).finish() {
+            Ok(("", monkeys)) => return Ok(monkeys),
+            Ok((rest, _)) => rest,
+            Err(err) => err.input,
+        };
+        // `many0` stops quietly at the first monkey it can't parse, so parse that one again to
+        // find out where it goes wrong
+        let rest = match monkey(rest).finish() {
+            Ok((rest, _)) => rest,
+            Err(err) => err.input,
+        };
+        Err(ParseError::at(input, rest, "expected a monkey's notes"))
     }
 }
 
@@ -116,7 +137,7 @@ impl Monkey {
         let level = self.apply_operation(n) / relief_factor;
         self.activity += 1;
 
-        if level % self.test_divisible_by == 0 {
+        if level.is_multiple_of(self.test_divisible_by) {
             Some(InspectionResult {
                 to: self.if_true_throw_to,
                 level,
@@ -134,51 +155,120 @@ impl Monkey {
     }
 }
 
-fn play_round(monkeys: &mut Vec<Monkey>, relief_factor: usize) {
+fn play_round(monkeys: &mut [Monkey], relief_factor: usize) {
     let x = monkeys
         .iter()
         .map(|m| m.test_divisible_by)
-        .fold(1, |acc, x| acc * x);
+        .product::<usize>();
 
     for n in 0..(monkeys.len()) {
-        loop {
-            match monkeys[n].inspect_next_item(relief_factor) {
-                Some(r) => monkeys[r.to].catch_item(r.level % x),
-                None => break,
-            }
+        while let Some(r) = monkeys[n].inspect_next_item(relief_factor) {
+            monkeys[r.to].catch_item(r.level % x);
         }
     }
 }
 
-fn monkey_business(monkeys: &Vec<Monkey>) -> usize {
+fn monkey_business(monkeys: &[Monkey]) -> usize {
     let mut activies: Vec<usize> = monkeys.iter().map(|n| n.activity).collect();
     activies.sort();
     let n = activies.len();
     activies[n - 1] * activies[n - 2]
 }
 
-fn read_input() -> Result<String, std::io::Error> {
-    use std::io::Read;
-    let mut s = String::new();
-    std::io::stdin().lock().read_to_string(&mut s)?;
-    return Ok(s);
+fn parse(input: &str) -> Result<Vec<Monkey>, aoc_common::ParseError> {
+    parser::parse_monkeys(input)
 }
 
-fn main() {
-    let s = read_input().unwrap();
-    let monkeys = parser::parse_monkeys(s.as_str()).unwrap();
-    {
-        let mut monkeys = monkeys.clone();
-        for _ in 0..20 {
-            play_round(&mut monkeys, 3);
-        }
-        println!("part 1: {}", monkey_business(&monkeys));
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let mut monkeys = parse(input)?;
+    let relief_factor: usize = RELIEF_FACTOR.value()?;
+    if relief_factor == 0 {
+        anyhow::bail!("the relief factor can't be 0");
+    }
+    for _ in 0..PART_1_ROUNDS.value::<usize>()? {
+        play_round(&mut monkeys, relief_factor);
+    }
+    Ok(monkey_business(&monkeys))
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let mut monkeys = parse(input)?;
+    for _ in 0..PART_2_ROUNDS.value::<usize>()? {
+        play_round(&mut monkeys, 1);
+    }
+    Ok(monkey_business(&monkeys))
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(
+    2022,
+    11,
+    parse: parse,
+    params: [PART_1_ROUNDS, PART_2_ROUNDS, RELIEF_FACTOR]
+);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 10605, part_2: 2713310158u64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn monkey() -> impl Strategy<Value = Monkey> {
+        let operation = prop_oneof![
+            (0..100usize).prop_map(Operation::Add),
+            (0..100usize).prop_map(Operation::Mul),
+            Just(Operation::Square),
+        ];
+        (
+            prop::collection::vec(0..100usize, 1..6),
+            operation,
+            1..30usize,
+            0..8usize,
+            0..8usize,
+        )
+            .prop_map(|(items, operation, divisor, if_true, if_false)| Monkey {
+                items: items.into(),
+                operation,
+                test_divisible_by: divisor,
+                if_true_throw_to: if_true,
+                if_false_throw_to: if_false,
+                activity: 0,
+            })
+    }
+
+    fn render(id: usize, monkey: &Monkey) -> String {
+        let items: Vec<String> = monkey.items.iter().map(usize::to_string).collect();
+        let operation = match monkey.operation {
+            Operation::Add(n) => format!("+ {n}"),
+            Operation::Mul(n) => format!("* {n}"),
+            Operation::Square => "* old".to_owned(),
+        };
+        format!(
+            "Monkey {id}:\n  Starting items: {}\n  Operation: new = old {operation}\n  \
+             Test: divisible by {}\n    If true: throw to monkey {}\n    \
+             If false: throw to monkey {}\n",
+            items.join(", "),
+            monkey.test_divisible_by,
+            monkey.if_true_throw_to,
+            monkey.if_false_throw_to,
+        )
     }
-    {
-        let mut monkeys = monkeys.clone();
-        for _ in 0..10000 {
-            play_round(&mut monkeys, 1);
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(monkeys in prop::collection::vec(monkey(), 0..8)) {
+            let notes: Vec<String> =
+                monkeys.iter().enumerate().map(|(id, m)| render(id, m)).collect();
+            prop_assert_eq!(parse(&notes.join("\n")).unwrap(), monkeys);
         }
-        println!("part 2: {}", monkey_business(&monkeys));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let input = include_str!("example.txt").replace("new = old + 6", "new = old - 6");
+        let err = parse(&input).unwrap_err();
+        assert_eq!((err.line, err.column), (10, 23));
     }
 }
@@ -1,47 +1,68 @@
-#[derive(Debug, PartialEq, Clone, Ord, Eq)]
+#[derive(Debug, PartialEq, Clone, Eq)]
 pub enum Item {
     List(Vec<Item>),
     Int(i32),
 }
 
-impl std::cmp::PartialOrd for Item {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+impl std::cmp::Ord for Item {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self, other) {
-            (Item::Int(a), Item::Int(b)) => a.partial_cmp(&b),
-            (Item::Int(_), Item::List(_)) => Item::List(vec![self.clone()]).partial_cmp(other),
-            (Item::List(_), Item::Int(_)) => self.partial_cmp(&Item::List(vec![other.clone()])),
-            (Item::List(a), Item::List(b)) => a.partial_cmp(&b),
+            (Item::Int(a), Item::Int(b)) => a.cmp(b),
+            (Item::Int(_), Item::List(_)) => Item::List(vec![self.clone()]).cmp(other),
+            (Item::List(_), Item::Int(_)) => self.cmp(&Item::List(vec![other.clone()])),
+            (Item::List(a), Item::List(b)) => a.cmp(b),
         }
     }
 }
 
+impl std::cmp::PartialOrd for Item {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 mod parser {
     use super::Item;
+    use aoc_common::ParseError;
     use nom::branch::alt;
     use nom::bytes::complete::tag;
     use nom::character::complete::{digit1, multispace0};
-    use nom::combinator::{map, map_res};
+    use nom::combinator::{cut, map, map_res};
+    use nom::error::{Error, ErrorKind};
     use nom::multi::separated_list0;
     use nom::sequence::{delimited, separated_pair};
-    use nom::IResult;
+    use nom::{Finish, IResult};
 
     fn int(input: &str) -> IResult<&str, Item> {
         map(map_res(digit1, |s: &str| s.parse()), Item::Int)(input)
     }
 
-    fn list(input: &str) -> IResult<&str, Item> {
-        map(
-            delimited(tag("["), separated_list0(tag(","), item), tag("]")),
-            Item::List,
-        )(input)
+    /// Packets nested deeper than this are rejected instead of overflowing the stack.
+    const MAX_DEPTH: usize = 100;
+
+    fn list(depth: usize) -> impl Fn(&str) -> IResult<&str, Item> {
+        move |input| {
+            if depth > MAX_DEPTH {
+                return Err(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge)));
+            }
+            map(
+                // Once a list is open, anything but a well-formed rest of the list is an error
+                delimited(
+                    tag("["),
+                    cut(separated_list0(tag(","), item(depth + 1))),
+                    cut(tag("]")),
+                ),
+                Item::List,
+            )(input)
+        }
     }
 
-    fn item(input: &str) -> IResult<&str, Item> {
-        alt((int, list))(input)
+    fn item(depth: usize) -> impl Fn(&str) -> IResult<&str, Item> {
+        move |input| alt((int, list(depth)))(input)
     }
 
     fn pair(input: &str) -> IResult<&str, (Item, Item)> {
-        separated_pair(item, tag("\n"), item)(input)
+        separated_pair(item(0), tag("\n"), item(0))(input)
     }
 
     pub fn parse_pairs(input: &str) -> IResult<&str, Vec<(Item, Item)>> {
@@ -49,21 +70,27 @@ mod parser {
     }
 
     pub fn parse_list(input: &str) -> IResult<&str, Vec<Item>> {
-        separated_list0(multispace0, item)(input)
+        separated_list0(multispace0, item(0))(input)
     }
-}
 
-fn read_input() -> Result<String, std::io::Error> {
-    use std::io::Read;
-    let mut s = String::new();
-    std::io::stdin().lock().read_to_string(&mut s)?;
-    return Ok(s);
+    /// Runs `parser` over all of `input`, pointing at where it stopped if that's not the end.
+    pub fn parse_all<'a, T>(
+        input: &'a str,
+        parser: impl Fn(&'a str) -> IResult<&'a str, T>,
+    ) -> Result<T, ParseError> {
+        const EXPECTED: &str = "expected a packet like `[1,[2,3]]`";
+        let (rest, message) = match parser(input).finish() {
+            Ok((rest, result)) if rest.trim().is_empty() => return Ok(result),
+            Ok((rest, _)) => (rest, EXPECTED),
+            Err(err) if err.code == ErrorKind::TooLarge => (err.input, "packet is nested too deep"),
+            Err(err) => (err.input, EXPECTED),
+        };
+        Err(ParseError::at(input, rest, message))
+    }
 }
 
-fn main() {
-    let input = read_input().unwrap();
-
-    let pairs = parser::parse_pairs(input.as_str()).unwrap().1;
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let pairs = parser::parse_all(input, parser::parse_pairs)?;
 
     let part_1_result = pairs
         .iter()
@@ -71,18 +98,77 @@ fn main() {
         .filter_map(|(n, (a, b))| if a < b { Some(n + 1) } else { None })
         .sum::<usize>();
 
-    println!("Part 1: {part_1_result}");
+    Ok(part_1_result)
+}
 
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
     let dividers = parser::parse_pairs("[[2]]\n[[6]]").unwrap().1;
     let (start, end) = dividers.first().unwrap();
 
-    let mut list = parser::parse_list(input.as_str()).unwrap().1;
+    let mut list = parser::parse_all(input, parser::parse_list)?;
     list.push(start.clone());
     list.push(end.clone());
     list.sort();
     let start_idx = list.iter().position(|x| x == start).unwrap() + 1;
     let end_idx = list.iter().position(|x| x == end).unwrap() + 1;
 
-    let part_2_result = start_idx * end_idx;
-    println!("Part 2: {start_idx} * {end_idx} = {part_2_result}");
+    Ok(start_idx * end_idx)
+}
+
+pub const DAY: aoc_common::Day =
+    aoc_common::day!(2022, 13, parse: |input| parser::parse_all(input, parser::parse_list));
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 13, part_2: 140 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn packet() -> impl Strategy<Value = Item> {
+        let leaf = (0..20i32).prop_map(Item::Int);
+        let item = leaf.prop_recursive(6, 64, 5, |inner| {
+            prop::collection::vec(inner, 0..5).prop_map(Item::List)
+        });
+        // Packets are always lists
+        prop::collection::vec(item, 0..5).prop_map(Item::List)
+    }
+
+    fn render(item: &Item) -> String {
+        match item {
+            Item::Int(n) => n.to_string(),
+            Item::List(items) => {
+                let items: Vec<String> = items.iter().map(render).collect();
+                format!("[{}]", items.join(","))
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(pairs in prop::collection::vec((packet(), packet()), 0..10)) {
+            let input: Vec<String> =
+                pairs.iter().map(|(a, b)| format!("{}\n{}", render(a), render(b))).collect();
+            let input = input.join("\n\n") + "\n";
+
+            let parsed = parser::parse_all(&input, parser::parse_pairs).unwrap();
+            prop_assert_eq!(&parsed, &pairs);
+            let packets: Vec<Item> = pairs.into_iter().flat_map(|(a, b)| [a, b]).collect();
+            prop_assert_eq!(parser::parse_all(&input, parser::parse_list).unwrap(), packets);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parser::parse_all("[1,2]\n[1,,2]\n", parser::parse_list).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        let err = parser::parse_all("[1,[2]\n", parser::parse_list).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 7));
+
+        let deep = "[".repeat(1000) + &"]".repeat(1000);
+        let err = parser::parse_all(&deep, parser::parse_list).unwrap_err();
+        assert_eq!(err.message, "packet is nested too deep");
+    }
 }
@@ -1,54 +1,68 @@
+use anyhow::bail;
+use aoc_common::{
+    image::{self, Image, Rgb},
+    visualize, Param, ParseError,
+};
+use aoc_grid::Pos;
+use itertools::Itertools;
+use nom::Finish;
 use std::collections::HashMap;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub struct Point {
-    x: i32,
-    y: i32,
-}
+const SAND_ORIGIN: Param = Param {
+    name: "sand-origin",
+    default: "500,0",
+    help: "Where the sand pours in from, as `x,y`",
+};
+
+/// Keeps the grid, and the rocks filled in between the corners of the paths, a sane size.
+const MAX_COORDINATE: i32 = 100_000;
 
 mod parser {
-    use super::Point;
+    use aoc_grid::Pos;
     use nom::bytes::complete::tag;
     use nom::character::complete::digit1;
-    use nom::combinator::{map, map_res};
+    use nom::combinator::{cut, map, map_res, verify};
     use nom::multi::separated_list1;
     use nom::sequence::separated_pair;
     use nom::IResult;
 
+    use super::MAX_COORDINATE;
+
     fn int(input: &str) -> IResult<&str, i32> {
-        map_res(digit1, |s: &str| s.parse())(input)
+        verify(map_res(digit1, |s: &str| s.parse()), |n| {
+            *n <= MAX_COORDINATE
+        })(input)
     }
 
-    fn point(input: &str) -> IResult<&str, Point> {
-        map(separated_pair(int, tag(","), int), |(x, y)| Point { x, y })(input)
+    fn point(input: &str) -> IResult<&str, Pos> {
+        map(separated_pair(int, tag(","), int), |(x, y)| Pos::new(x, y))(input)
     }
 
-    pub fn point_list(input: &str) -> IResult<&str, Vec<Point>> {
-        separated_list1(tag(" -> "), point)(input)
+    pub fn point_list(input: &str) -> IResult<&str, Vec<Pos>> {
+        separated_list1(tag(" -> "), cut(point))(input)
     }
 }
 
-fn unfold_lines<T>(input: T) -> Vec<Point>
+fn unfold_lines<T>(input: T) -> Vec<Pos>
 where
-    T: IntoIterator<Item = Point>,
+    T: IntoIterator<Item = Pos>,
 {
     let mut iter = input.into_iter();
     let mut result = Vec::new();
 
     if let Some(start) = iter.next() {
-        result.push(start.clone());
-        let mut p = start.clone();
-        while let Some(q) = iter.next() {
+        result.push(start);
+        let mut p = start;
+        for q in iter {
             while p != q {
-                p.x += (q.x - p.x).signum();
-                p.y += (q.y - p.y).signum();
-                result.push(p.clone());
+                p += Pos::new((q.x - p.x).signum(), (q.y - p.y).signum());
+                result.push(p);
             }
-            p = q.clone();
+            p = q;
         }
     }
 
-    return result;
+    result
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -58,25 +72,26 @@ enum Field {
     Rock,
 }
 
+#[derive(Debug)]
 struct Grid {
-    cells: HashMap<Point, Field>,
+    cells: HashMap<Pos, Field>,
     bottom: i32,
 }
 
 impl Grid {
     pub fn from_rock_positions<T>(rocks: T) -> Self
     where
-        T: IntoIterator<Item = Point>,
+        T: IntoIterator<Item = Pos>,
     {
         let mut cells = HashMap::new();
         for rock in rocks {
             cells.insert(rock, Field::Rock);
         }
         let bottom = cells.keys().map(|p| p.y).max().unwrap_or(0);
-        return Grid { cells, bottom };
+        Grid { cells, bottom }
     }
 
-    pub fn get(&self, p: &Point) -> Field {
+    pub fn get(&self, p: &Pos) -> Field {
         if p.y > self.bottom + 1 {
             Field::Rock
         } else {
@@ -84,52 +99,242 @@ impl Grid {
         }
     }
 
-    pub fn drop_sand(&mut self, origin: &Point) -> Point {
+    pub fn drop_sand(&mut self, origin: &Pos) -> Pos {
         let p = self.flow(origin);
-        self.cells.insert(p.clone(), Field::Sand);
-        return p;
+        self.cells.insert(p, Field::Sand);
+        p
     }
 
     pub fn amount_of_sand(&self) -> usize {
         self.cells.values().filter(|x| **x == Field::Sand).count()
     }
 
-    fn flow(&self, origin: &Point) -> Point {
-        let mut p = origin.clone();
+    /// Draws the cave down to the lowest rock, with the sand poured so far.
+    fn render(&self, origin: &Pos) -> String {
+        let (min_x, max_x) = self
+            .cells
+            .keys()
+            .map(|p| p.x)
+            .chain([origin.x])
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let min = Pos::new(min_x - 1, 0);
+        let max = Pos::new(max_x + 1, self.bottom + 1);
+        aoc_grid::render(min, max, |p| match self.cells.get(&p) {
+            _ if p == *origin => '+',
+            Some(Field::Rock) => '#',
+            Some(Field::Sand) => 'o',
+            _ => '.',
+        })
+    }
+
+    /// Paints the cave like [`Self::render`] draws it, with the floor when `floor` is set.
+    fn image(&self, origin: &Pos, floor: bool) -> Image {
+        let (min_x, max_x) = self
+            .cells
+            .keys()
+            .map(|p| p.x)
+            .chain([origin.x])
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let min = Pos::new(min_x - 1, 0);
+        let height = self.bottom + if floor { 3 } else { 2 };
+        Image::from_fn((max_x - min_x + 3) as usize, height as usize, |x, y| {
+            let p = min + Pos::new(x as i32, y as i32);
+            match self.cells.get(&p) {
+                _ if p == *origin => Rgb(255, 60, 60),
+                Some(Field::Rock) => Rgb(110, 110, 110),
+                Some(Field::Sand) => Rgb(230, 190, 90),
+                _ if floor && p.y == self.bottom + 2 => Rgb(110, 110, 110),
+                _ => Rgb::BLACK,
+            }
+        })
+        .scaled(4)
+    }
+
+    fn flow(&self, origin: &Pos) -> Pos {
+        let mut p = *origin;
 
         loop {
-            let y = p.y + 1;
-            let candidates = [
-                Point { x: p.x, y },
-                Point { x: p.x - 1, y },
-                Point { x: p.x + 1, y },
-            ];
-            match candidates.iter().find(|q| self.get(q) == Field::Air) {
-                Some(next_p) => p = next_p.clone(),
+            let candidates = [Pos::new(0, 1), Pos::new(-1, 1), Pos::new(1, 1)].map(|d| p + d);
+            match candidates.into_iter().find(|q| self.get(q) == Field::Air) {
+                Some(next_p) => p = next_p,
                 None => return p,
             }
         }
     }
 }
 
-fn main() {
-    let input: Vec<Vec<Point>> = std::io::stdin()
+/// Whether every segment of the path is horizontal or vertical.
+fn is_straight(path: &[Pos]) -> bool {
+    path.iter()
+        .tuple_windows()
+        .all(|(p, q)| p.x == q.x || p.y == q.y)
+}
+
+fn parse(input: &str) -> Result<Grid, ParseError> {
+    let paths: Vec<Vec<Pos>> = input
         .lines()
-        .filter_map(|s| Some(parser::point_list(&s.unwrap()).ok()?.1))
-        .collect();
+        .map(|s| {
+            let rest = match parser::point_list(s).finish() {
+                Ok(("", points)) if is_straight(&points) => return Ok(points),
+                Ok(("", _)) => return Err(ParseError::at(input, s, "paths only go straight")),
+                Ok((rest, _)) => rest,
+                Err(err) => err.input,
+            };
+            Err(ParseError::at(
+                input,
+                rest,
+                "expected a path like `498,4 -> 498,6 -> 496,6`",
+            ))
+        })
+        .collect::<Result<_, _>>()?;
 
-    let rocks: Vec<Point> = input.into_iter().flat_map(unfold_lines).collect();
-    let mut grid = Grid::from_rock_positions(rocks);
+    let rocks: Vec<Pos> = paths.into_iter().flat_map(unfold_lines).collect();
+    Ok(Grid::from_rock_positions(rocks))
+}
 
-    let origin = Point { x: 500, y: 0 };
+/// Where the sand pours in from, which has to be in the air above the floor.
+fn sand_origin(grid: &Grid) -> anyhow::Result<Pos> {
+    match SAND_ORIGIN.list::<i32>()?[..] {
+        [x, y] => pour_in_at(grid, Pos::new(x, y)),
+        _ => bail!("the sand origin is a position like `500,0`"),
+    }
+}
+
+/// Checks the sand can pour in at `origin`, which is bounded like the rocks are.
+fn pour_in_at(grid: &Grid, origin: Pos) -> anyhow::Result<Pos> {
+    if [origin.x, origin.y]
+        .iter()
+        .any(|n| !(0..=MAX_COORDINATE).contains(n))
+    {
+        bail!("the sand origin's coordinates go from 0 to {MAX_COORDINATE}");
+    }
+    if grid.get(&origin) != Field::Air {
+        bail!("the sand can't pour in at {},{}", origin.x, origin.y);
+    }
+    Ok(origin)
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let mut grid = parse(input)?;
+    let origin = sand_origin(&grid)?;
 
-    while grid.drop_sand(&origin).y < grid.bottom {
-        continue;
+    loop {
+        let p = grid.drop_sand(&origin);
+        if p.y >= grid.bottom {
+            break;
+        }
+        if p == origin {
+            bail!("the sand piles up to the origin without ever flowing into the abyss");
+        }
+        visualize::frame(|| grid.render(&origin));
     }
-    println!("Part 1: {}", grid.amount_of_sand() - 1);
+    image::export("2022_day_14_part_1", || grid.image(&origin, false))?;
+    Ok(grid.amount_of_sand() - 1)
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let mut grid = parse(input)?;
+    let origin = sand_origin(&grid)?;
 
     while grid.drop_sand(&origin) != origin {
-        continue;
+        visualize::frame(|| grid.render(&origin));
+    }
+    image::export("2022_day_14_part_2", || grid.image(&origin, true))?;
+    Ok(grid.amount_of_sand())
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2022, 14, parse: parse, params: [SAND_ORIGIN]);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 24, part_2: 93 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    /// A path of alternating horizontal and vertical segments.
+    fn path() -> impl Strategy<Value = Vec<Pos>> {
+        let start = (400..600, 0..200).prop_map(|(x, y)| Pos::new(x, y));
+        (start, prop::collection::vec(-20..20i32, 1..6)).prop_map(|(start, lengths)| {
+            let mut path = vec![start];
+            for (i, length) in lengths.into_iter().enumerate() {
+                let p = *path.last().unwrap();
+                let q = if i % 2 == 0 {
+                    Pos::new(p.x + length, p.y)
+                } else {
+                    Pos::new(p.x, (p.y + length).max(0))
+                };
+                path.push(q);
+            }
+            path
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(paths in prop::collection::vec(path(), 0..10)) {
+            let input: String = paths
+                .iter()
+                .map(|path| {
+                    let points: Vec<String> = path.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+                    points.join(" -> ") + "\n"
+                })
+                .collect();
+
+            let mut rocks = HashSet::new();
+            for (p, q) in paths.iter().flat_map(|path| path.iter().tuple_windows()) {
+                for x in p.x.min(q.x)..=p.x.max(q.x) {
+                    for y in p.y.min(q.y)..=p.y.max(q.y) {
+                        rocks.insert(Pos::new(x, y));
+                    }
+                }
+            }
+
+            let grid = parse(&input).unwrap();
+            prop_assert!(grid.cells.values().all(|field| *field == Field::Rock));
+            prop_assert_eq!(grid.cells.keys().copied().collect::<HashSet<_>>(), rocks);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("498,4 -> 498,6\n503,4 -> 502,5\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "paths only go straight")
+        );
+        let err = parse("498,4 -> 498,6 ->\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 15));
+        let err = parse("498,4 -> 1000000,4\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 10));
+    }
+
+    #[test]
+    fn test_sand_origin() {
+        let grid = parse("498,4 -> 498,6\n").unwrap();
+        assert_eq!(
+            pour_in_at(&grid, Pos::new(500, 0)).unwrap(),
+            Pos::new(500, 0)
+        );
+        for origin in [
+            Pos::new(i32::MAX, 0),
+            Pos::new(500, -1),
+            Pos::new(0, 100_001),
+        ] {
+            let err = pour_in_at(&grid, origin).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "the sand origin's coordinates go from 0 to 100000"
+            );
+        }
+        let err = pour_in_at(&grid, Pos::new(498, 5)).unwrap_err();
+        assert_eq!(err.to_string(), "the sand can't pour in at 498,5");
     }
-    println!("Part 2: {}", grid.amount_of_sand());
 }
@@ -1,5 +1,4 @@
-use std::{io, io::prelude::*};
-
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Shape {
     Rock,
     Paper,
@@ -10,18 +9,18 @@ fn score(shapes: (Shape, Shape)) -> u32 {
     match shapes {
         (Shape::Rock, Shape::Rock) => 3 + 1,
         (Shape::Rock, Shape::Paper) => 6 + 2,
-        (Shape::Rock, Shape::Scissors) => 0 + 3,
-        (Shape::Paper, Shape::Rock) => 0 + 1,
+        (Shape::Rock, Shape::Scissors) => 3,
+        (Shape::Paper, Shape::Rock) => 1,
         (Shape::Paper, Shape::Paper) => 3 + 2,
         (Shape::Paper, Shape::Scissors) => 6 + 3,
         (Shape::Scissors, Shape::Rock) => 6 + 1,
-        (Shape::Scissors, Shape::Paper) => 0 + 2,
+        (Shape::Scissors, Shape::Paper) => 2,
         (Shape::Scissors, Shape::Scissors) => 3 + 3,
     }
 }
 
-fn part_1_parse(s: &String) -> (Shape, Shape) {
-    match s.trim() {
+fn part_1_parse(s: &str) -> Result<(Shape, Shape), &'static str> {
+    Ok(match s.trim() {
         "A X" => (Shape::Rock, Shape::Rock),
         "A Y" => (Shape::Rock, Shape::Paper),
         "A Z" => (Shape::Rock, Shape::Scissors),
@@ -31,12 +30,12 @@ fn part_1_parse(s: &String) -> (Shape, Shape) {
         "C X" => (Shape::Scissors, Shape::Rock),
         "C Y" => (Shape::Scissors, Shape::Paper),
         "C Z" => (Shape::Scissors, Shape::Scissors),
-        _ => unreachable!(),
-    }
+        _ => return Err("expected `A`, `B` or `C`, a space, and `X`, `Y` or `Z`"),
+    })
 }
 
-fn part_2_parse(s: &String) -> (Shape, Shape) {
-    match s.trim() {
+fn part_2_parse(s: &str) -> Result<(Shape, Shape), &'static str> {
+    Ok(match s.trim() {
         "A X" => (Shape::Rock, Shape::Scissors),
         "A Y" => (Shape::Rock, Shape::Rock),
         "A Z" => (Shape::Rock, Shape::Paper),
@@ -46,24 +45,53 @@ fn part_2_parse(s: &String) -> (Shape, Shape) {
         "C X" => (Shape::Scissors, Shape::Paper),
         "C Y" => (Shape::Scissors, Shape::Scissors),
         "C Z" => (Shape::Scissors, Shape::Rock),
-        _ => unreachable!(),
-    }
+        _ => return Err("expected `A`, `B` or `C`, a space, and `X`, `Y` or `Z`"),
+    })
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    Ok(aoc_common::parse_lines(input, part_1_parse)?
+        .into_iter()
+        .fold(0, |acc, shapes| acc + score(shapes)))
 }
 
-fn main() {
-    let input: Vec<String> = io::stdin().lock().lines().map(|s| s.unwrap()).collect();
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    Ok(aoc_common::parse_lines(input, part_2_parse)?
+        .into_iter()
+        .fold(0, |acc, shapes| acc + score(shapes)))
+}
 
-    let part_1_score = input
-        .iter()
-        .map(part_1_parse)
-        .fold(0, |acc, shapes| acc + score(shapes));
+pub const DAY: aoc_common::Day =
+    aoc_common::day!(2022, 2, parse: |input| aoc_common::parse_lines(input, part_1_parse));
 
-    println!("\nPart 1: {part_1_score}");
+aoc_common::examples! {
+    "example.txt" => { part_1: 15, part_2: 12 },
+}
 
-    let part_2_score = input
-        .iter()
-        .map(part_2_parse)
-        .fold(0, |acc, shapes| acc + score(shapes));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
 
-    println!("\nPart 2: {part_2_score}");
+    fn shape() -> impl Strategy<Value = Shape> {
+        prop_oneof![Just(Shape::Rock), Just(Shape::Paper), Just(Shape::Scissors)]
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(rounds in prop::collection::vec((shape(), shape()), 0..20)) {
+            let letter = |shape, letters: [char; 3]| match shape {
+                Shape::Rock => letters[0],
+                Shape::Paper => letters[1],
+                Shape::Scissors => letters[2],
+            };
+            let input: String = rounds
+                .iter()
+                .map(|(a, b)| {
+                    format!("{} {}\n", letter(*a, ['A', 'B', 'C']), letter(*b, ['X', 'Y', 'Z']))
+                })
+                .collect();
+            prop_assert_eq!(aoc_common::parse_lines(&input, part_1_parse).unwrap(), rounds);
+        }
+    }
 }
@@ -1,53 +1,130 @@
-use std::{collections::HashSet, io, io::prelude::*};
+use std::collections::HashSet;
 
-use itertools::Itertools;
+use aoc_common::ParseError;
 
 fn priority(c: char) -> u32 {
-    if c >= 'a' && c <= 'z' {
+    if c.is_ascii_lowercase() {
         (c as u32 - 'a' as u32) + 1
-    } else if c >= 'A' && c <= 'Z' {
+    } else if c.is_ascii_uppercase() {
         (c as u32 - 'A' as u32) + 27
     } else {
-        panic!("invalid character")
+        unreachable!("parse only accepts letters")
     }
 }
 
-fn main() {
-    let rucksacks: Vec<(HashSet<char>, HashSet<char>)> = io::stdin()
-        .lock()
-        .lines()
-        .map(|s| {
-            let s = s.unwrap();
-            let x = s.len() / 2;
-            let a = HashSet::from_iter(s[0..x].chars());
-            let b = HashSet::from_iter(s[x..].chars());
-            (a, b)
-        })
-        .collect();
+type Rucksack = (HashSet<char>, HashSet<char>);
 
-    let part_1 = rucksacks
-        .iter()
-        .map(|sack| sack.0.intersection(&sack.1).last().unwrap())
-        .map(|c| priority(*c))
-        .sum::<u32>();
+fn parse(input: &str) -> Result<Vec<Rucksack>, ParseError> {
+    aoc_common::parse_lines(input, |s| {
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(format!("items are letters, found {c:?}"));
+        }
+        if s.len() % 2 != 0 {
+            return Err(format!(
+                "expected two equal compartments, found {} items",
+                s.len()
+            ));
+        }
+        let x = s.len() / 2;
+        let a = HashSet::from_iter(s[0..x].chars());
+        let b = HashSet::from_iter(s[x..].chars());
+        Ok((a, b))
+    })
+}
 
-    println!("part 1: {part_1}");
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    let rucksacks = parse(input)?;
+    let mut sum = 0;
+    for (idx, sack) in rucksacks.iter().enumerate() {
+        let Some(c) = sack.0.intersection(&sack.1).next() else {
+            let message = "the compartments have no item in common";
+            return Err(ParseError::on_line(input, idx, message).into());
+        };
+        sum += priority(*c);
+    }
+    Ok(sum)
+}
 
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    let rucksacks = parse(input)?;
     let mut sum = 0;
-    for mut chunk in &rucksacks.iter().chunks(3) {
-        let a = chunk.next().unwrap();
+    for (idx, group) in rucksacks.chunks(3).enumerate() {
+        let [a, b, c] = group else {
+            let message = format!("expected groups of 3 rucksacks, found {}", group.len());
+            return Err(ParseError::on_line(input, 3 * idx, message).into());
+        };
         let a: HashSet<char> = a.0.union(&a.1).copied().collect();
-        let b = chunk.next().unwrap();
         let b: HashSet<char> = b.0.union(&b.1).copied().collect();
-        let c = chunk.next().unwrap();
         let c: HashSet<char> = c.0.union(&c.1).copied().collect();
 
         let mut set: HashSet<char> = a.intersection(&b).copied().collect();
         set = set.intersection(&c).copied().collect();
 
-        let chr = set.iter().last().unwrap();
+        let Some(chr) = set.iter().next() else {
+            let message = "the group's rucksacks have no item in common";
+            return Err(ParseError::on_line(input, 3 * idx, message).into());
+        };
         sum += priority(*chr);
     }
+    Ok(sum)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2022, 3, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 157, part_2: 70 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Both compartments of a rucksack, as the letters in them.
+    fn rucksack() -> impl Strategy<Value = (String, String)> {
+        let letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+        (1..20usize).prop_flat_map(move |len| {
+            let compartment = prop::collection::vec(prop::sample::select(letters.clone()), len)
+                .prop_map(String::from_iter);
+            (compartment.clone(), compartment)
+        })
+    }
 
-    println!("part 2: {sum}");
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(rucksacks in prop::collection::vec(rucksack(), 0..10)) {
+            let input: String = rucksacks.iter().map(|(a, b)| format!("{a}{b}\n")).collect();
+            let expected: Vec<Rucksack> = rucksacks
+                .iter()
+                .map(|(a, b)| (a.chars().collect(), b.chars().collect()))
+                .collect();
+            prop_assert_eq!(parse(&input).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_nothing_in_common() {
+        let err = part_1("abAb\nabcd\n").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "the compartments have no item in common")
+        );
+        let err = part_2("aa\nbb\ncc\n").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (1, "the group's rucksacks have no item in common")
+        );
+    }
+
+    #[test]
+    fn test_incomplete_group() {
+        let err = part_2("aa\naa\naa\naa\n").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (4, "expected groups of 3 rucksacks, found 1")
+        );
+    }
 }
@@ -1,10 +1,10 @@
 mod assignment {
+    #[derive(Debug, PartialEq)]
     pub struct Assignment {
-        range: std::ops::RangeInclusive<u32>,
+        pub range: std::ops::RangeInclusive<u32>,
     }
 
-    #[derive(Debug)]
-    pub struct ParseError;
+    const EXPECTED: &str = "expected a pair of ranges like `2-4,6-8`";
 
     impl Assignment {
         pub fn contains(&self, other: &Self) -> bool {
@@ -17,52 +17,72 @@ mod assignment {
     }
 
     impl std::str::FromStr for Assignment {
-        type Err = ParseError;
+        type Err = &'static str;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let mut iter = s
-                .splitn(2, "-")
-                .map(|s| s.parse::<u32>().or(Err(ParseError)));
-            let a = iter.next().ok_or(ParseError)??;
-            let b = iter.next().ok_or(ParseError)??;
+            let mut iter = s.splitn(2, "-").map(|s| s.parse::<u32>().or(Err(EXPECTED)));
+            let a = iter.next().ok_or(EXPECTED)??;
+            let b = iter.next().ok_or(EXPECTED)??;
             Ok(Assignment { range: a..=b })
         }
     }
 
     pub type AssignmentPair = (Assignment, Assignment);
 
-    pub fn parse_pair(s: &str) -> Result<AssignmentPair, ParseError> {
+    pub fn parse_pair(s: &str) -> Result<AssignmentPair, &'static str> {
         let mut iter = s.splitn(2, ",").map(|s| s.parse::<Assignment>());
-        let a = iter.next().ok_or(ParseError)??;
-        let b = iter.next().ok_or(ParseError)??;
+        let a = iter.next().ok_or(EXPECTED)??;
+        let b = iter.next().ok_or(EXPECTED)??;
         Ok((a, b))
     }
 }
 
 use assignment::AssignmentPair;
 
-#[derive(Debug)]
-enum Error {
-    ParseError(assignment::ParseError),
-    IOError(std::io::Error),
+fn parse(input: &str) -> Result<Vec<AssignmentPair>, aoc_common::ParseError> {
+    aoc_common::parse_lines(input, assignment::parse_pair)
 }
 
-fn read_input() -> Result<Vec<AssignmentPair>, Error> {
-    std::io::stdin()
-        .lines()
-        .map(|s| s.map_err(Error::IOError))
-        .map(|s| assignment::parse_pair(s?.as_str()).map_err(Error::ParseError))
-        .collect()
-}
-
-pub fn main() {
-    let pairs = read_input().unwrap();
-    let part_1_result = pairs
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let pairs = parse(input)?;
+    Ok(pairs
         .iter()
         .filter(|(a, b)| a.contains(b) || b.contains(a))
-        .count();
-    println!("part 1: {part_1_result}");
+        .count())
+}
 
-    let part_2_result = pairs.iter().filter(|(a, b)| a.overlaps(b)).count();
-    println!("part 2: {part_2_result}");
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let pairs = parse(input)?;
+    Ok(pairs.iter().filter(|(a, b)| a.overlaps(b)).count())
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2022, 4, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 2, part_2: 4 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assignment::Assignment;
+    use proptest::prelude::*;
+
+    fn assignment() -> impl Strategy<Value = Assignment> {
+        (0..100u32, 0..100u32).prop_map(|(a, b)| Assignment { range: a..=b })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(
+            pairs in prop::collection::vec((assignment(), assignment()), 0..20),
+        ) {
+            let range = |a: &Assignment| format!("{}-{}", a.range.start(), a.range.end());
+            let input: String = pairs
+                .iter()
+                .map(|(a, b)| format!("{},{}\n", range(a), range(b)))
+                .collect();
+            prop_assert_eq!(parse(&input).unwrap(), pairs);
+        }
+    }
 }
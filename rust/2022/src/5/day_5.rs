@@ -1,12 +1,15 @@
-use std::io::Read;
-
-#[derive(Debug)]
-pub struct ParseError;
+use aoc_common::ParseError;
 
 mod stacks {
+    use aoc_common::ParseError;
     use std::collections::LinkedList;
     use std::str::FromStr;
 
+    /// The byte offset of `line` into `s`, which it must be a slice of.
+    fn offset_in(s: &str, line: &str) -> usize {
+        line.as_ptr() as usize - s.as_ptr() as usize
+    }
+
     type Stack = LinkedList<char>;
 
     #[derive(Debug)]
@@ -17,7 +20,7 @@ mod stacks {
 
     #[derive(Clone, Debug)]
     pub struct Stacks {
-        stacks: Vec<Stack>,
+        pub(super) stacks: Vec<Stack>,
     }
 
     impl Stacks {
@@ -55,20 +58,32 @@ mod stacks {
     }
 
     impl FromStr for Stacks {
-        type Err = super::ParseError;
+        type Err = ParseError;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
             let mut stacks: Vec<LinkedList<char>> = vec![];
             let mut lines = s.lines().rev();
 
-            for _ in 0..(lines.next().ok_or(super::ParseError)?.len() / 4 + 1) {
+            let numbers = lines
+                .next()
+                .ok_or_else(|| ParseError::at_end(s, "expected a drawing of the stacks"))?;
+            for _ in 0..(numbers.len() / 4 + 1) {
                 stacks.push(LinkedList::new());
             }
 
             for line in lines {
                 for (i, c) in line.chars().skip(1).step_by(4).enumerate() {
                     if c != ' ' {
-                        stacks[i].push_back(c);
+                        stacks
+                            .get_mut(i)
+                            .ok_or_else(|| {
+                                ParseError::at_offset(
+                                    s,
+                                    offset_in(s, line) + 4 * i + 1,
+                                    "no stack numbered below this crate",
+                                )
+                            })?
+                            .push_back(c);
                     }
                 }
             }
@@ -83,7 +98,7 @@ mod instruction {
     use regex::Regex;
     use std::str::FromStr;
 
-    #[derive(Debug)]
+    #[derive(Debug, PartialEq)]
     pub struct Instruction {
         pub quantity: usize,
         pub from: usize,
@@ -91,73 +106,140 @@ mod instruction {
     }
 
     impl FromStr for Instruction {
-        type Err = super::ParseError;
+        type Err = &'static str;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
             lazy_static! {
                 static ref RE: Regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
             }
 
-            let captures = RE.captures(s).ok_or(super::ParseError)?;
+            let captures = RE.captures(s).ok_or("expected `move N from A to B`")?;
             let captures = captures.iter().skip(1);
             let mut captures = captures.filter_map(|c| c?.as_str().parse::<usize>().ok());
-            let quantity = captures.next().ok_or(super::ParseError)?;
-            let from = captures.next().ok_or(super::ParseError)?;
-            let to = captures.next().ok_or(super::ParseError)?;
+            let mut number = || captures.next().ok_or("number is too large");
+            let quantity = number()?;
+            let from = number()?;
+            let to = number()?;
 
             Ok(Instruction {
                 quantity,
-                from: from.checked_sub(1).ok_or(super::ParseError)?,
-                to: to.checked_sub(1).ok_or(super::ParseError)?,
+                from: from.checked_sub(1).ok_or("stacks are numbered from 1")?,
+                to: to.checked_sub(1).ok_or("stacks are numbered from 1")?,
             })
         }
     }
 }
 
-fn read_input() -> Result<String, std::io::Error> {
-    let mut s = String::new();
-    std::io::stdin().lock().read_to_string(&mut s)?;
-    Ok(s)
-}
-
 fn parse(s: &str) -> Result<(stacks::Stacks, Vec<instruction::Instruction>), ParseError> {
-    let mut iter = s.splitn(2, "\n\n");
-
-    let stacks_str = iter.next().ok_or(ParseError)?;
+    let (stacks_str, instructions_str) = s.split_once("\n\n").ok_or_else(|| {
+        ParseError::at_end(s, "expected a blank line between the stacks and the moves")
+    })?;
     let stacks = stacks_str.parse::<stacks::Stacks>()?;
 
-    let instructions_str = iter.next().ok_or(ParseError)?;
     let instructions = instructions_str
         .lines()
-        .map(str::parse::<instruction::Instruction>)
+        .map(|line| line.parse().map_err(|err| ParseError::at(s, line, err)))
         .collect::<Result<_, _>>()?;
 
     Ok((stacks, instructions))
 }
 
-fn main() {
-    let input = read_input().unwrap();
-    let (stacks, instructions) = parse(input.as_str()).unwrap();
+pub fn part_1(input: &str) -> anyhow::Result<String> {
+    let (mut stacks, instructions) = parse(input)?;
+    instructions
+        .iter()
+        .try_for_each(|instruction::Instruction { quantity, from, to }| {
+            (0..*quantity).try_for_each(|_| stacks.move_item(*from, *to))
+        })
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    stacks.top_row().map_err(|e| anyhow::anyhow!("{e:?}"))
+}
 
-    {
-        let mut stacks = stacks.clone();
-        instructions
-            .iter()
-            .try_for_each(|instruction::Instruction { quantity, from, to }| {
-                (0..*quantity).try_for_each(|_| stacks.move_item(*from, *to))
-            })
-            .unwrap();
-        println!("Part 1: {}", stacks.top_row().unwrap());
+pub fn part_2(input: &str) -> anyhow::Result<String> {
+    let (mut stacks, instructions) = parse(input)?;
+    instructions
+        .iter()
+        .try_for_each(|instruction::Instruction { quantity, from, to }| {
+            stacks.move_items(*quantity, *from, *to)
+        })
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    stacks.top_row().map_err(|e| anyhow::anyhow!("{e:?}"))
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2022, 5, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: "CMZ", part_2: "MCD" },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::instruction::Instruction;
+    use super::*;
+    use proptest::prelude::*;
+
+    fn stacks() -> impl Strategy<Value = Vec<Vec<char>>> {
+        let crate_ = prop::char::range('A', 'Z');
+        prop::collection::vec(prop::collection::vec(crate_, 0..8), 1..=9)
+    }
+
+    fn instruction(stacks: usize) -> impl Strategy<Value = Instruction> {
+        (1..50usize, 0..stacks, 0..stacks).prop_map(|(quantity, from, to)| Instruction {
+            quantity,
+            from,
+            to,
+        })
+    }
+
+    /// Draws the stacks bottom up, like the puzzle does.
+    fn render(stacks: &[Vec<char>], instructions: &[Instruction]) -> String {
+        let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let mut drawing = String::new();
+        for level in (0..height).rev() {
+            let crates = stacks.iter().map(|stack| match stack.get(level) {
+                Some(c) => format!("[{c}]"),
+                None => "   ".to_owned(),
+            });
+            drawing += &crates.collect::<Vec<_>>().join(" ");
+            drawing += "\n";
+        }
+        let numbers = (1..=stacks.len()).map(|n| format!(" {n} "));
+        drawing += &numbers.collect::<Vec<_>>().join(" ");
+        drawing += "\n\n";
+        for i in instructions {
+            drawing += &format!("move {} from {} to {}\n", i.quantity, i.from + 1, i.to + 1);
+        }
+        drawing
     }
 
-    {
-        let mut stacks = stacks.clone();
-        instructions
-            .iter()
-            .try_for_each(|instruction::Instruction { quantity, from, to }| {
-                stacks.move_items(*quantity, *from, *to)
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(
+            (stacks, instructions) in stacks().prop_flat_map(|stacks| {
+                let n = stacks.len();
+                (Just(stacks), prop::collection::vec(instruction(n), 0..10))
             })
-            .unwrap();
-        println!("Part 2: {}", stacks.top_row().unwrap());
+        ) {
+            let (parsed, parsed_instructions) = parse(&render(&stacks, &instructions)).unwrap();
+            let parsed: Vec<Vec<char>> = parsed
+                .stacks
+                .iter()
+                .map(|stack| stack.iter().copied().collect())
+                .collect();
+            prop_assert_eq!(parsed, stacks);
+            prop_assert_eq!(parsed_instructions, instructions);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("[A]\n 1 \n\nmove 1 from 0 to 1\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (4, "stacks are numbered from 1")
+        );
+        let err = parse("[A] [B]\n 1 \n\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+        assert!(parse("[A]\n 1 \n").is_err());
     }
 }
@@ -1,7 +1,20 @@
+use aoc_common::Param;
 use std::collections::HashSet;
 
+const PACKET_MARKER: Param = Param {
+    name: "packet-marker",
+    default: "4",
+    help: "How many different characters in a row make a start-of-packet marker",
+};
+
+const MESSAGE_MARKER: Param = Param {
+    name: "message-marker",
+    default: "14",
+    help: "How many different characters in a row make a start-of-message marker",
+};
+
 fn substrings(s: String, size: usize) -> impl Iterator<Item = String> {
-    (0..(s.len() - size)).map(move |n| s[n..(n + size)].to_owned())
+    (0..s.len().saturating_sub(size)).map(move |n| s[n..(n + size)].to_owned())
 }
 
 fn find_first_marker(s: &str, size: usize) -> Option<usize> {
@@ -13,10 +26,26 @@ fn find_first_marker(s: &str, size: usize) -> Option<usize> {
     None
 }
 
-fn main() {
-    let line = std::io::stdin().lines().next().unwrap().unwrap();
-    println!("part 1: {}", find_first_marker(line.as_str(), 4).unwrap());
-    println!("part 2: {}", find_first_marker(line.as_str(), 14).unwrap());
+fn find_marker(input: &str, size: &Param) -> anyhow::Result<usize> {
+    let size = size.value::<usize>()?;
+    if size == 0 {
+        anyhow::bail!("markers are at least one character long");
+    }
+    find_first_marker(input.trim(), size).ok_or_else(|| anyhow::anyhow!("no marker found"))
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    find_marker(input, &PACKET_MARKER)
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    find_marker(input, &MESSAGE_MARKER)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2022, 6, params: [PACKET_MARKER, MESSAGE_MARKER]);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 7, part_2: 19 },
 }
 
 #[cfg(test)]
@@ -1,10 +1,10 @@
 mod parser {
     use nom::branch::alt;
-    use nom::bytes::complete::tag;
+    use nom::bytes::complete::{is_not, tag};
     use nom::character::complete::{alphanumeric1, digit1, space1};
-    use nom::combinator::{map, map_res, rest};
+    use nom::combinator::{map, map_res, verify};
     use nom::sequence::{preceded, separated_pair};
-    use nom::IResult;
+    use nom::{Finish, IResult};
 
     #[derive(Debug, PartialEq, Clone)]
     pub enum Command {
@@ -26,12 +26,6 @@ mod parser {
         Output(Output),
     }
 
-    #[derive(Debug)]
-    pub enum ParseError {
-        Error(String),
-        Unparsed(String),
-    }
-
     fn command(input: &str) -> IResult<&str, Command> {
         let chdir_arg = alt((tag("/"), tag(".."), alphanumeric1));
         let chdir = map(preceded(tag("cd "), chdir_arg), |s| match s {
@@ -45,7 +39,9 @@ mod parser {
 
     fn output(input: &str) -> IResult<&str, Output> {
         let size = map_res(digit1, |s: &str| s.parse::<u32>());
-        let file = map(separated_pair(size, space1, rest), |(size, name)| {
+        // Names are joined onto the current path, so they mustn't be able to climb out of it
+        let name = verify(is_not("/ \t"), |s: &str| s != "." && s != "..");
+        let file = map(separated_pair(size, space1, name), |(size, name)| {
             Output::File(name.to_string(), size)
         });
         let dir = map(preceded(tag("dir "), alphanumeric1), |s: &str| {
@@ -58,34 +54,54 @@ mod parser {
         alt((map(command, Line::Command), map(output, Line::Output)))(input)
     }
 
-    pub fn parse_line(input: &str) -> Result<Line, ParseError> {
-        match line(input) {
+    /// Parses a whole line, or returns the part of it that couldn't be parsed.
+    pub fn parse_line(input: &str) -> Result<Line, &str> {
+        match line(input).finish() {
             Ok(("", result)) => Ok(result),
-            Ok((rest, _)) => Err(ParseError::Unparsed(rest.to_string())),
-            Err(err) => Err(ParseError::Error(err.to_string())),
+            Ok((rest, _)) => Err(rest),
+            Err(err) => Err(err.input),
         }
     }
 }
 
-use parser::*;
+use anyhow::anyhow;
+use aoc_common::{Param, ParseError};
+use parser::{Command, Line, Output};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+const DISK_SIZE: Param = Param {
+    name: "disk-size",
+    default: "70000000",
+    help: "The size of the disk",
+};
+
+const NEEDED_SPACE: Param = Param {
+    name: "needed-space",
+    default: "30000000",
+    help: "The free space the update needs",
+};
 
-fn build_file_index<T>(parsed_lines: T) -> HashMap<PathBuf, u32>
-where
-    T: IntoIterator<Item = Line>,
-{
+fn build_file_index(input: &str) -> Result<HashMap<PathBuf, u32>, ParseError> {
     let mut files = HashMap::<PathBuf, u32>::new();
     let root = "/".parse::<PathBuf>().unwrap();
     let mut path = root.clone();
 
-    for line in parsed_lines {
-        match line {
+    for line in input.lines() {
+        let parsed = parser::parse_line(line).map_err(|rest| {
+            ParseError::at(
+                input,
+                rest,
+                "expected a command like `$ cd a` or a listing like `123 a.txt`",
+            )
+        })?;
+        match parsed {
             Line::Command(Command::ChangeDirRoot) => path = root.clone(),
-            Line::Command(Command::ChangeDirUp) => match path.pop() {
-                false => panic!("already at root!"),
-                true => (),
-            },
+            Line::Command(Command::ChangeDirUp) => {
+                if !path.pop() {
+                    return Err(ParseError::at(input, line, "already at the root directory"));
+                }
+            }
             Line::Command(Command::ChangeDirDown(s)) => path.push(s),
             Line::Command(Command::List) => (),
             Line::Output(Output::Directory(_)) => (),
@@ -95,56 +111,128 @@ where
         }
     }
 
-    return files;
+    Ok(files)
 }
 
-fn build_directory_index(file_index: &HashMap<PathBuf, u32>) -> HashMap<PathBuf, u32> {
-    let mut index = HashMap::<PathBuf, u32>::new();
+/// The size of every directory, including the root even when there are no files at all.
+fn build_directory_index(file_index: &HashMap<PathBuf, u32>) -> HashMap<PathBuf, u64> {
+    let mut index = HashMap::<PathBuf, u64>::from([(PathBuf::from("/"), 0)]);
 
     for (path, size) in file_index {
         let mut dir = path.parent().unwrap().to_owned();
         loop {
-            if index.contains_key(&dir) {
-                *index.get_mut(&dir).unwrap() += size;
-            } else {
-                index.insert(dir.clone(), *size);
-            }
+            *index.entry(dir.clone()).or_default() += u64::from(*size);
             if !dir.pop() {
                 break;
             }
         }
     }
 
-    return index;
+    index
 }
 
-fn main() {
-    let parsed_lines = std::io::stdin()
-        .lines()
-        .map(|s| parser::parse_line(s.unwrap().as_str()))
-        .collect::<Result<Vec<Line>, ParseError>>()
-        .unwrap();
-
-    let file_index = build_file_index(parsed_lines);
-    let directory_index = build_directory_index(&file_index);
+fn parse(input: &str) -> Result<HashMap<PathBuf, u64>, ParseError> {
+    let file_index = build_file_index(input)?;
+    Ok(build_directory_index(&file_index))
+}
 
+pub fn part_1(input: &str) -> anyhow::Result<u64> {
+    let directory_index = parse(input)?;
     let small_dirs_size = directory_index
         .values()
         .filter(|x| **x < 100000)
-        .sum::<u32>();
+        .sum::<u64>();
+    Ok(small_dirs_size)
+}
 
-    println!("part 1: {small_dirs_size}");
+pub fn part_2(input: &str) -> anyhow::Result<u64> {
+    let directory_index = parse(input)?;
 
-    let total_size = 70000000;
-    let needed_space = 30000000;
-    let used_space = directory_index.values().max().unwrap();
-    let need_to_free = needed_space - (total_size - used_space);
+    let total_size: u64 = DISK_SIZE.value()?;
+    let needed_space: u64 = NEEDED_SPACE.value()?;
+    let used_space = directory_index[Path::new("/")];
+    let free_space = total_size
+        .checked_sub(used_space)
+        .ok_or_else(|| anyhow!("the files take up {used_space}, more than the disk holds"))?;
+    let need_to_free = needed_space.saturating_sub(free_space);
 
     let size_of_selected_dir = directory_index
         .values()
         .filter(|x| **x >= need_to_free)
         .min()
-        .unwrap();
+        .ok_or_else(|| {
+            anyhow!("the update needs {needed_space}, more than the disk holds when it's empty")
+        })?;
+
+    Ok(*size_of_selected_dir)
+}
+
+pub const DAY: aoc_common::Day =
+    aoc_common::day!(2022, 7, parse: parse, params: [DISK_SIZE, NEEDED_SPACE]);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 95437, part_2: 24933642 },
+}
 
-    println!("part 2: {size_of_selected_dir}");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeMap;
+
+    /// Files by their directories and name. Directory names have no dots, so they can't clash
+    /// with the file names.
+    fn files() -> impl Strategy<Value = BTreeMap<(Vec<String>, String), u32>> {
+        let dirs = prop::collection::vec("[a-z]{1,2}", 0..4);
+        let name = "[a-z]{1,3}\\.[a-z]{1,3}";
+        prop::collection::btree_map((dirs, name), 1..1_000_000u32, 0..20)
+    }
+
+    /// A terminal session that visits every file from the root and back up again.
+    fn render(files: &BTreeMap<(Vec<String>, String), u32>) -> String {
+        let mut session = String::new();
+        for ((dirs, name), size) in files {
+            session += "$ cd /\n";
+            for dir in dirs {
+                session += &format!("$ ls\ndir {dir}\n$ cd {dir}\n");
+            }
+            session += &format!("$ ls\n{size} {name}\n");
+            session += &"$ cd ..\n".repeat(dirs.len());
+        }
+        session
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(files in files()) {
+            let mut expected = HashMap::<PathBuf, u64>::from([(PathBuf::from("/"), 0)]);
+            for ((dirs, name), size) in &files {
+                let path = Path::new("/").join(dirs.join("/")).join(name);
+                for dir in path.ancestors().skip(1) {
+                    *expected.entry(dir.to_owned()).or_default() += u64::from(*size);
+                }
+            }
+            prop_assert_eq!(parse(&render(&files)).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("$ cd /\n$ cd ..\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "already at the root directory")
+        );
+        let err = parse("$ cd /\n$ ls\n12 ../escape\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 4));
+        let err = parse("$ cd /\n$ ls\n99999999999 a.txt\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+
+    #[test]
+    fn test_no_files() {
+        let input = "$ cd /\n$ ls\n";
+        assert_eq!(part_1(input).unwrap(), 0);
+        assert_eq!(part_2(input).unwrap(), 0);
+    }
 }
@@ -1,11 +1,8 @@
+use aoc_common::{visualize, ParseError};
+use aoc_grid::Pos;
+use nom::Finish;
 use std::collections::HashSet;
 
-#[derive(Debug)]
-enum Error {
-    ParseError(String),
-    IOError(std::io::Error),
-}
-
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Direction {
     Up,
@@ -37,17 +34,35 @@ mod parser {
     }
 }
 
-fn parse_input() -> Result<Vec<(Direction, usize)>, Error> {
-    std::io::stdin()
+/// The most steps the rope takes over all the motions, which keeps the knots well within `i32`
+/// and the steps in memory
+const MAX_STEPS: usize = 10_000_000;
+
+fn parse_input(input: &str) -> Result<Vec<(Direction, usize)>, ParseError> {
+    let mut steps = 0;
+    input
         .lines()
-        .map(
-            |s| match parser::parse(s.map_err(Error::IOError)?.as_str()) {
-                Ok(("", result)) => Ok(result),
-                Ok((rest, _)) => Err(Error::ParseError(format!("unparsed: {rest:?}"))),
-                Err(e) => Err(Error::ParseError(e.to_string())),
-            },
-        )
-        .collect::<Result<Vec<(Direction, usize)>, Error>>()
+        .map(|s| {
+            let rest = match parser::parse(s).finish() {
+                Ok(("", (direction, count))) => {
+                    steps = count.saturating_add(steps);
+                    if steps > MAX_STEPS {
+                        let count = s.trim_start_matches(|c: char| !c.is_ascii_digit());
+                        let message = format!("the rope can't take more than {MAX_STEPS} steps");
+                        return Err(ParseError::at(input, count, message));
+                    }
+                    return Ok((direction, count));
+                }
+                Ok((rest, _)) => rest,
+                Err(err) => err.input,
+            };
+            Err(ParseError::at(
+                input,
+                rest,
+                "expected a direction `U`, `D`, `L` or `R` and a number of steps",
+            ))
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone)]
@@ -84,9 +99,7 @@ struct Rope {
 
 impl Rope {
     pub fn new(length: usize) -> Self {
-        let sections = std::iter::repeat(RopeSection { x: 0, y: 0 })
-            .take(length)
-            .collect();
+        let sections = std::iter::repeat_n(RopeSection { x: 0, y: 0 }, length).collect();
         Self { sections }
     }
 
@@ -106,23 +119,107 @@ impl Rope {
     pub fn track_tail(&mut self, directions: &Vec<Direction>) -> HashSet<(i32, i32)> {
         let mut visited_by_tail: HashSet<(i32, i32)> = HashSet::new();
         for dir in directions {
-            self.go(&dir);
+            self.go(dir);
             visited_by_tail.insert(self.tail());
+            visualize::frame(|| self.render(&visited_by_tail));
         }
         visited_by_tail
     }
+
+    /// Draws the area around the head, with the knots on top of the positions the tail visited.
+    fn render(&self, visited_by_tail: &HashSet<(i32, i32)>) -> String {
+        let head = &self.sections[0];
+        // The rope moves up with increasing `y`, rows are drawn top down
+        let min = Pos::new(head.x - 30, -head.y - 15);
+        let max = Pos::new(head.x + 30, -head.y + 15);
+        aoc_grid::render(min, max, |p| {
+            let (x, y) = (p.x, -p.y);
+            let knot = self.sections.iter().position(|s| (s.x, s.y) == (x, y));
+            match knot {
+                Some(0) => 'H',
+                Some(_) if self.sections.len() == 2 => 'T',
+                Some(n) => char::from_digit(n as u32, 10).unwrap_or('#'),
+                None if (x, y) == (0, 0) => 's',
+                None if visited_by_tail.contains(&(x, y)) => '#',
+                None => '.',
+            }
+        })
+    }
 }
 
-fn main() {
-    let input = parse_input().unwrap();
-    let directions: Vec<Direction> = input
+fn directions(input: &str) -> anyhow::Result<Vec<Direction>> {
+    let input = parse_input(input)?;
+    Ok(input
         .iter()
-        .flat_map(|(dir, n)| std::iter::repeat(dir.clone()).take(*n))
-        .collect();
+        .flat_map(|(dir, n)| std::iter::repeat_n(dir.clone(), *n))
+        .collect())
+}
 
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let directions = directions(input)?;
     let visited_by_tail = Rope::new(2).track_tail(&directions);
-    println!("part 1: {}", visited_by_tail.len());
+    Ok(visited_by_tail.len())
+}
 
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let directions = directions(input)?;
     let visited_by_tail = Rope::new(10).track_tail(&directions);
-    println!("part 2: {}", visited_by_tail.len());
+    Ok(visited_by_tail.len())
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2022, 9, parse: parse_input);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 13, part_2: 1 },
+    "example_2.txt" => { part_2: 36 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn motion() -> impl Strategy<Value = (Direction, usize)> {
+        let direction = prop_oneof![
+            Just(Direction::Up),
+            Just(Direction::Down),
+            Just(Direction::Left),
+            Just(Direction::Right),
+        ];
+        (direction, 1..100usize)
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(motions in prop::collection::vec(motion(), 0..20)) {
+            let input: String = motions
+                .iter()
+                .map(|(direction, steps)| {
+                    let direction = match direction {
+                        Direction::Up => 'U',
+                        Direction::Down => 'D',
+                        Direction::Left => 'L',
+                        Direction::Right => 'R',
+                    };
+                    format!("{direction} {steps}\n")
+                })
+                .collect();
+            prop_assert_eq!(parse_input(&input).unwrap(), motions);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("R 4\nU 4x\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        let err = parse_input("X 4").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        let err = parse_input("R 4\nU 18446744073709551615\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        let err = parse_input("R 4\nU 99999999999\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.message.as_str()),
+            (2, 3, "the rope can't take more than 10000000 steps")
+        );
+    }
 }
@@ -0,0 +1,58 @@
+#[path = "1/day_1.rs"]
+pub mod day_1;
+
+#[path = "2/day_2.rs"]
+pub mod day_2;
+
+#[path = "3/day_3.rs"]
+pub mod day_3;
+
+#[path = "4/day_4.rs"]
+pub mod day_4;
+
+#[path = "5/day_5.rs"]
+pub mod day_5;
+
+#[path = "6/day_6.rs"]
+pub mod day_6;
+
+#[path = "7/day_7.rs"]
+pub mod day_7;
+
+#[path = "8/day_8.rs"]
+pub mod day_8;
+
+#[path = "9/day_9.rs"]
+pub mod day_9;
+
+#[path = "10/day_10.rs"]
+pub mod day_10;
+
+#[path = "11/day_11.rs"]
+pub mod day_11;
+
+#[path = "12/day_12.rs"]
+pub mod day_12;
+
+#[path = "13/day_13.rs"]
+pub mod day_13;
+
+#[path = "14/day_14.rs"]
+pub mod day_14;
+
+pub const DAYS: &[aoc_common::Day] = &[
+    day_1::DAY,
+    day_2::DAY,
+    day_3::DAY,
+    day_4::DAY,
+    day_5::DAY,
+    day_6::DAY,
+    day_7::DAY,
+    day_8::DAY,
+    day_9::DAY,
+    day_10::DAY,
+    day_11::DAY,
+    day_12::DAY,
+    day_13::DAY,
+    day_14::DAY,
+];
@@ -1,7 +1,7 @@
 use regex::Regex;
 
-fn read_input<T: std::io::BufRead>(f: T) -> Result<Vec<String>, std::io::Error> {
-    f.lines().map(|s| s.map(|s| s.trim().to_owned())).collect()
+fn read_input(input: &str) -> Vec<&str> {
+    input.lines().map(str::trim).collect()
 }
 
 fn digit_str_to_int(s: &str) -> Result<u32, std::num::ParseIntError> {
@@ -15,7 +15,7 @@ fn digit_str_to_int(s: &str) -> Result<u32, std::num::ParseIntError> {
         "seven" => 7,
         "eight" => 8,
         "nine" => 9,
-        s => u32::from_str_radix(s, 10)?,
+        s => s.parse()?,
     };
     Ok(value)
 }
@@ -32,17 +32,28 @@ fn calibration_value(re: &Regex, s: &str) -> Option<u32> {
     Some(first * 10 + last)
 }
 
-fn main() {
-    let input = read_input(std::io::stdin().lock()).unwrap();
-    let part_1_regex = Regex::new(r"\d").unwrap();
-    let calibration_value_sum = input.iter().fold(0, |acc, s| {
-        acc + calibration_value(&part_1_regex, s).unwrap()
-    });
-    println!("Part 1: {calibration_value_sum}");
-
-    let part_2_regex = Regex::new(r"\d|one|two|three|four|five|six|seven|eight|nine").unwrap();
-    let calibration_value_sum = input.iter().fold(0, |acc, s| {
-        acc + calibration_value(&part_2_regex, s).unwrap()
-    });
-    println!("Part 2: {calibration_value_sum}");
+fn calibration_value_sum(re: &Regex, input: &str) -> anyhow::Result<u32> {
+    read_input(input).iter().try_fold(0, |acc, s| {
+        let value = calibration_value(re, s).ok_or_else(|| {
+            aoc_common::ParseError::at(input, s, "no calibration value on this line")
+        })?;
+        Ok(acc + value)
+    })
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    let part_1_regex = Regex::new(r"[0-9]")?;
+    calibration_value_sum(&part_1_regex, input)
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    let part_2_regex = Regex::new(r"[0-9]|one|two|three|four|five|six|seven|eight|nine")?;
+    calibration_value_sum(&part_2_regex, input)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2023, 1, parse: part_1);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 142 },
+    "example_2.txt" => { part_2: 281 },
 }
@@ -0,0 +1,4 @@
+#[path = "1/day_1.rs"]
+pub mod day_1;
+
+pub const DAYS: &[aoc_common::Day] = &[day_1::DAY];
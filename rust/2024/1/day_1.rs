@@ -1,8 +1,8 @@
 use anyhow::anyhow;
 use itertools::Itertools;
-use std::{io::stdin, str::FromStr};
+use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 struct ParsedLine(u32, u32);
 
 impl FromStr for ParsedLine {
@@ -16,30 +16,54 @@ impl FromStr for ParsedLine {
         if v.len() == 2 {
             Ok(ParsedLine(v[0], v[1]))
         } else {
-            Err(anyhow!("too few values"))
+            Err(anyhow!("expected two location ids"))
         }
     }
 }
 
-fn main() -> anyhow::Result<()> {
-    let input = stdin()
-        .lines()
-        .map(|s| s.unwrap().parse())
-        .collect::<anyhow::Result<Vec<ParsedLine>>>()?;
+fn parse(input: &str) -> anyhow::Result<(Vec<u32>, Vec<u32>)> {
+    let input = aoc_common::parse_lines(input, str::parse::<ParsedLine>)?;
 
     let left = input.iter().map(|x| x.0).sorted().collect_vec();
     let right = input.iter().map(|x| x.1).sorted().collect_vec();
+    Ok((left, right))
+}
 
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    let (left, right) = parse(input)?;
     let total_differance: u32 = left.iter().zip(&right).map(|(a, b)| a.abs_diff(*b)).sum();
-    println!("Part 1: {total_differance:?}");
+    Ok(total_differance)
+}
 
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    let (left, right) = parse(input)?;
     let right_counts = right.iter().counts();
 
     let similarity_score: u32 = left
         .iter()
         .map(|x| x * (*right_counts.get(x).unwrap_or(&0) as u32))
         .sum();
-    println!("Part 2: {similarity_score:?}");
+    Ok(similarity_score)
+}
 
-    Ok(())
+pub const DAY: aoc_common::Day = aoc_common::day!(2024, 1, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 11, part_2: 31 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(pairs in prop::collection::vec(any::<(u32, u32)>(), 0..20)) {
+            let input: String = pairs.iter().map(|(a, b)| format!("{a}   {b}\n")).collect();
+            let expected: Vec<ParsedLine> = pairs.iter().map(|(a, b)| ParsedLine(*a, *b)).collect();
+            let parsed = aoc_common::parse_lines(&input, str::parse::<ParsedLine>).unwrap();
+            prop_assert_eq!(parsed, expected);
+        }
+    }
 }
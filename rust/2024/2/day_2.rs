@@ -1,7 +1,7 @@
 use itertools::Itertools;
-use std::{io::stdin, ops::Deref, str::FromStr};
+use std::{ops::Deref, str::FromStr};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 struct ParsedLine(Vec<u32>);
 
 impl FromStr for ParsedLine {
@@ -47,16 +47,18 @@ fn is_safe<'a, T: Iterator<Item = &'a u32> + Clone>(report: T) -> bool {
     iter.clone().all(ascending) || iter.all(descending)
 }
 
-fn main() -> anyhow::Result<()> {
-    let input = stdin()
-        .lines()
-        .map(|s| s.unwrap().parse())
-        .collect::<anyhow::Result<Vec<ParsedLine>>>()?;
+fn parse(input: &str) -> Result<Vec<ParsedLine>, aoc_common::ParseError> {
+    aoc_common::parse_lines(input, str::parse)
+}
 
-    let part_1 = input.iter().filter(|report| is_safe(report.iter())).count();
-    println!("Part 1: {part_1:?}",);
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let input = parse(input)?;
+    Ok(input.iter().filter(|report| is_safe(report.iter())).count())
+}
 
-    let part_2 = input
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let input = parse(input)?;
+    Ok(input
         .iter()
         .filter(|report| {
             report.iter().dampen().any(|report| {
@@ -64,9 +66,31 @@ fn main() -> anyhow::Result<()> {
                 is_safe(report.iter().copied())
             })
         })
-        .count();
+        .count())
+}
 
-    println!("Part 2: {part_2:?}",);
+pub const DAY: aoc_common::Day = aoc_common::day!(2024, 2, parse: parse);
 
-    Ok(())
+aoc_common::examples! {
+    "example.txt" => { part_1: 2, part_2: 4 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(
+            reports in prop::collection::vec(prop::collection::vec(any::<u32>(), 1..10), 0..20),
+        ) {
+            let input: String = reports
+                .iter()
+                .map(|levels| format!("{}\n", levels.iter().join(" ")))
+                .collect();
+            let expected: Vec<ParsedLine> = reports.into_iter().map(ParsedLine).collect();
+            prop_assert_eq!(parse(&input).unwrap(), expected);
+        }
+    }
 }
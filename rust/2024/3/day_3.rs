@@ -1,38 +1,45 @@
-use itertools::Itertools;
+use aoc_common::ParseError;
 use regex::Regex;
-use std::io::{stdin, Read};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 enum Token {
     Do,
     DoNot,
     Mul(u32, u32),
 }
 
-fn main() {
-    let mut input = String::new();
-    stdin().read_to_string(&mut input).unwrap();
-
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
     let re = Regex::new(r"do\(\)|don't\(\)|mul\((\d+),(\d+)\)").unwrap();
-    let tokens = re
-        .captures_iter(&input)
-        .map(|m| match &m[0] {
-            "do()" => Token::Do,
-            "don't()" => Token::DoNot,
-            _ => Token::Mul(m[1].parse::<u32>().unwrap(), m[2].parse::<u32>().unwrap()),
+    re.captures_iter(input)
+        .map(|m| {
+            let number = |idx| {
+                let s = m.get(idx).unwrap().as_str();
+                s.parse::<u32>()
+                    .map_err(|_| ParseError::at(input, s, "number is too large"))
+            };
+            Ok(match &m[0] {
+                "do()" => Token::Do,
+                "don't()" => Token::DoNot,
+                _ => Token::Mul(number(1)?, number(2)?),
+            })
         })
-        .collect_vec();
+        .collect()
+}
 
-    let part_1: u32 = tokens
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    let tokens = tokenize(input)?;
+    Ok(tokens
         .iter()
         .filter_map(|token| match token {
             Token::Mul(a, b) => Some(a * b),
             _ => None,
         })
-        .sum();
-    println!("part 1: {part_1}");
+        .sum())
+}
 
-    let part_2: u32 = tokens
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    let tokens = tokenize(input)?;
+    Ok(tokens
         .iter()
         .fold((0, true), |(acc, enabled), token| match token {
             Token::Do => (acc, true),
@@ -40,6 +47,44 @@ fn main() {
             Token::Mul(a, b) if enabled => (acc + a * b, true),
             Token::Mul(_, _) => (acc, false),
         })
-        .0;
-    println!("part 2: {part_2}");
+        .0)
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2024, 3, parse: tokenize);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 161, part_2: 48 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn token() -> impl Strategy<Value = Token> {
+        prop_oneof![
+            Just(Token::Do),
+            Just(Token::DoNot),
+            (any::<u32>(), any::<u32>()).prop_map(|(a, b)| Token::Mul(a, b)),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(
+            tokens in prop::collection::vec((token(), "[!@#$%^&*<> \n\\[\\]]*"), 0..20),
+        ) {
+            // Corrupted memory between the instructions, none of which could start one
+            let input: String = tokens
+                .iter()
+                .map(|(token, junk)| match token {
+                    Token::Do => format!("do(){junk}"),
+                    Token::DoNot => format!("don't(){junk}"),
+                    Token::Mul(a, b) => format!("mul({a},{b}){junk}"),
+                })
+                .collect();
+            let expected: Vec<Token> = tokens.into_iter().map(|(token, _)| token).collect();
+            prop_assert_eq!(tokenize(&input).unwrap(), expected);
+        }
+    }
 }
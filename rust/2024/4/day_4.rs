@@ -1,89 +1,92 @@
-use std::{
-    char,
-    collections::HashMap,
-    fmt::Display,
-    io::{stdin, Read},
-    str::FromStr,
-};
+use aoc_common::ParseError;
+use aoc_grid::{Grid, Pos, OFFSETS_8};
+use std::{fmt::Display, str::FromStr};
 
 #[derive(Debug)]
 struct WordSearch {
-    grid: HashMap<(i32, i32), char>,
+    grid: Grid<char>,
 }
 
 impl FromStr for WordSearch {
-    type Err = anyhow::Error;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.split_whitespace();
-        let grid: HashMap<_, _> = lines
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .map(move |(x, char)| ((x as i32, y as i32), char))
-            })
-            .collect();
+        let grid = s
+            .parse()
+            .map_err(|err: aoc_grid::ParseError| err.locate(s))?;
         Ok(Self { grid })
     }
 }
 
 impl Display for WordSearch {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let (max_x, max_y) = self.grid.keys().max().unwrap().clone();
-        for y in 0..=max_y {
-            for x in 0..=max_x {
-                write!(f, "{}", self.grid[&(x, y)])?;
-            }
-            if y < max_y {
-                write!(f, "\n")?;
-            }
-        }
-        Ok(())
+        write!(f, "{}", self.grid)
     }
 }
 
 impl WordSearch {
-    fn is_word(&self, s: &str, pos: &(i32, i32), direction: &(i32, i32)) -> bool {
-        let (x, y) = pos;
-        let (dx, dy) = direction;
+    fn is_word(&self, s: &str, pos: Pos, direction: Pos) -> bool {
         s.chars()
             .enumerate()
-            .map(|(i, c)| ((x + dx * (i as i32), (y + dy * (i as i32))), c))
-            .all(|(pos, c)| self.grid.get(&pos) == Some(&c))
+            .map(|(i, c)| (pos + direction * i as i32, c))
+            .all(|(pos, c)| self.grid.get(pos) == Some(&c))
     }
 
     pub fn count_words(&self, s: &str) -> usize {
-        #[rustfmt::skip]
-        let directions: [(i32, i32); 8] = [
-            (-1,  1), (0,  1), (1,  1),
-            (-1,  0),          (1,  0),
-            (-1, -1), (0, -1), (1, -1),
-        ];
         self.grid
-            .keys()
-            .flat_map(|pos| directions.map(|dir| ((*pos).clone(), dir.clone())))
-            .filter(|(pos, dir)| self.is_word(s, pos, dir))
+            .positions()
+            .flat_map(|pos| OFFSETS_8.map(|dir| (pos, dir)))
+            .filter(|(pos, dir)| self.is_word(s, *pos, *dir))
             .count()
     }
 
     #[rustfmt::skip]
     pub fn count_xmas(&self, s: &str) -> usize {
+        let p = Pos::new;
         self.grid
-            .keys()
-            .filter(|(x, y)| {
-                (self.is_word(s, &(x - 1, y - 1), &(1,  1)) || self.is_word(s, &(x + 1, y + 1), &(-1, -1))) &&
-                (self.is_word(s, &(x - 1, y + 1), &(1, -1)) || self.is_word(s, &(x + 1, y - 1), &(-1,  1)))
+            .positions()
+            .filter(|pos| {
+                (self.is_word(s, *pos + p(-1, -1), p(1,  1)) || self.is_word(s, *pos + p(1,  1), p(-1, -1))) &&
+                (self.is_word(s, *pos + p(-1,  1), p(1, -1)) || self.is_word(s, *pos + p(1, -1), p(-1,  1)))
             })
             .count()
     }
 }
 
-fn main() -> anyhow::Result<()> {
-    let mut input = String::new();
-    stdin().read_to_string(&mut input)?;
-    let word_search = WordSearch::from_str(&input)?;
-    // println!("{word_search}");
-    println!("part 1: {}", word_search.count_words("XMAS"));
-    println!("part 2: {}", word_search.count_xmas("MAS"));
-    Ok(())
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let word_search = WordSearch::from_str(input)?;
+    Ok(word_search.count_words("XMAS"))
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let word_search = WordSearch::from_str(input)?;
+    Ok(word_search.count_xmas("MAS"))
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2024, 4, parse: WordSearch::from_str);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 18, part_2: 9 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// The rows of a word search of any size.
+    fn rows() -> impl Strategy<Value = Vec<String>> {
+        (1..10usize, 1..10usize).prop_flat_map(|(width, height)| {
+            let row = prop::string::string_regex(&format!("[XMAS]{{{width}}}")).unwrap();
+            prop::collection::vec(row, height)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(rows in rows()) {
+            let input: String = rows.iter().map(|row| format!("{row}\n")).collect();
+            let word_search = input.parse::<WordSearch>().unwrap();
+            prop_assert_eq!(word_search.to_string(), rows.join("\n"));
+        }
+    }
 }
@@ -1,20 +1,17 @@
 use anyhow::anyhow;
+use aoc_common::ParseError;
 use itertools::Itertools;
 use regex::Regex;
-use std::{
-    cell::LazyCell,
-    io::{stdin, Read},
-    str::FromStr,
-};
+use std::{str::FromStr, sync::LazyLock};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct PageOrderingRule {
     page: u32,
     must_be_before: u32,
 }
 
-const PAGE_ORDERING_RULE_REGEX: LazyCell<Regex> =
-    LazyCell::new(|| Regex::new(r"^(\d+)\|(\d+)$").unwrap());
+static PAGE_ORDERING_RULE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d+)\|(\d+)$").unwrap());
 
 impl FromStr for PageOrderingRule {
     type Err = anyhow::Error;
@@ -24,12 +21,12 @@ impl FromStr for PageOrderingRule {
                 page: captures[1].parse::<u32>()?,
                 must_be_before: captures[2].parse::<u32>()?,
             }),
-            None => Err(anyhow::anyhow!("error parsing: {s}")),
+            None => Err(anyhow!("expected a rule like `47|53`")),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 struct PageUpdates {
     pages: Vec<u32>,
 }
@@ -82,35 +79,41 @@ impl PageUpdates {
     }
 }
 
-fn read_input() -> anyhow::Result<(Vec<PageOrderingRule>, Vec<PageUpdates>)> {
-    let mut buf = String::new();
-    stdin().read_to_string(&mut buf)?;
-    let mut bits = buf.split("\n\n");
-    let page_ordering_rules = bits
-        .next()
-        .ok_or(anyhow!("parse error"))?
-        .split_whitespace()
-        .map(|s| s.parse::<PageOrderingRule>())
-        .collect::<Result<Vec<PageOrderingRule>, _>>()?;
-
-    let page_updates = bits
-        .next()
-        .ok_or(anyhow!("parse error"))?
-        .split_whitespace()
-        .map(|s| s.parse::<PageUpdates>())
-        .collect::<Result<Vec<PageUpdates>, _>>()?;
-
-    Ok((page_ordering_rules, page_updates))
-}
+fn parse(input: &str) -> Result<(Vec<PageOrderingRule>, Vec<PageUpdates>), ParseError> {
+    fn parse_words<T: FromStr<Err = anyhow::Error>>(
+        input: &str,
+        section: &str,
+    ) -> Result<Vec<T>, ParseError> {
+        section
+            .split_whitespace()
+            .map(|s| s.parse::<T>().map_err(|err| ParseError::at(input, s, err)))
+            .collect()
+    }
 
-fn main() -> anyhow::Result<()> {
-    let (rules, page_updates) = read_input()?;
+    let (rules, updates) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::at_end(
+            input,
+            "expected a blank line between the rules and the updates",
+        )
+    })?;
+    Ok((parse_words(input, rules)?, parse_words(input, updates)?))
+}
 
-    let (correct, incorrect): (Vec<_>, Vec<_>) =
-        page_updates.iter().partition(|x| x.is_correct(&rules));
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    let (rules, page_updates) = parse(input)?;
+    Ok(page_updates
+        .iter()
+        .filter(|x| x.is_correct(&rules))
+        .map(|x| x.middle_page())
+        .sum())
+}
 
-    let part_1: u32 = correct.iter().map(|x| x.middle_page()).sum();
-    println!("part 1: {part_1}",);
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    let (rules, page_updates) = parse(input)?;
+    let incorrect = page_updates
+        .iter()
+        .filter(|x| !x.is_correct(&rules))
+        .collect_vec();
 
     let rules = {
         let mut t = rules.clone();
@@ -119,10 +122,66 @@ fn main() -> anyhow::Result<()> {
     };
 
     let corrected = incorrect.iter().map(|x| x.sort(&rules)).collect_vec();
-    assert!(corrected.iter().all(|x| x.is_correct(&rules)));
+    if !corrected.iter().all(|x| x.is_correct(&rules)) {
+        return Err(anyhow!("could not correct all page updates"));
+    }
+
+    Ok(corrected.iter().map(|x| x.middle_page()).sum())
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2024, 5, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 143, part_2: 123 },
+}
 
-    let part_2: u32 = corrected.iter().map(|x| x.middle_page()).sum();
-    println!("part 2: {part_2}",);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
 
-    Ok(())
+    fn rule() -> impl Strategy<Value = PageOrderingRule> {
+        (10..100u32, 10..100u32).prop_map(|(page, must_be_before)| PageOrderingRule {
+            page,
+            must_be_before,
+        })
+    }
+
+    fn update() -> impl Strategy<Value = PageUpdates> {
+        prop::collection::vec(10..100u32, 1..10).prop_map(|pages| PageUpdates { pages })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(
+            rules in prop::collection::vec(rule(), 1..20),
+            updates in prop::collection::vec(update(), 0..10),
+        ) {
+            let mut input: String = rules
+                .iter()
+                .map(|r| format!("{}|{}\n", r.page, r.must_be_before))
+                .collect();
+            input += "\n";
+            for update in &updates {
+                input += &update.pages.iter().join(",");
+                input += "\n";
+            }
+
+            let (parsed_rules, parsed_updates) = parse(&input).unwrap();
+            prop_assert_eq!(parsed_rules, rules);
+            prop_assert_eq!(parsed_updates, updates);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("47|53\n47-61\n\n75,47\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (2, "expected a rule like `47|53`")
+        );
+        let err = parse("47|53\n\n75,x,47\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert!(parse("47|53\n").is_err());
+    }
 }
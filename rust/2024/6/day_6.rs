@@ -1,160 +1,537 @@
-use anyhow::anyhow;
-use itertools::Itertools;
+use aoc_common::{parallel, visualize, ParseError};
+use aoc_grid::{Direction, Grid, Pos};
 use std::{
-    collections::HashSet,
-    io::{stdin, Read},
+    collections::{HashMap, HashSet},
+    fmt::Display,
     str::FromStr,
 };
 
-#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
-struct Pos {
-    x: i32,
-    y: i32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Guard {
+    pos: Pos,
+    facing: Direction,
 }
 
-impl Pos {
-    pub fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-
-    pub fn walk(&self, dir: Direction) -> Self {
-        match dir {
-            Direction::Up => Self {
-                x: self.x,
-                y: self.y - 1,
-            },
-            Direction::Right => Self {
-                x: self.x + 1,
-                y: self.y,
-            },
-            Direction::Down => Self {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Direction::Left => Self {
-                x: self.x - 1,
-                y: self.y,
-            },
+/// How a guard's patrol ends. Every turn and every move forward is a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// Walked off the map across the `edge` the guard was facing on step `step`
+    Exits { edge: Direction, step: usize },
+    /// From step `start` on, goes round the same `len` steps forever
+    Loops { start: usize, len: usize },
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Exits { edge, step } => {
+                let edge = match edge {
+                    Direction::Up => "top",
+                    Direction::Right => "right",
+                    Direction::Down => "bottom",
+                    Direction::Left => "left",
+                };
+                write!(f, "leaves across the {edge} edge on step {step}")
+            }
+            Outcome::Loops { start, len } => {
+                write!(f, "goes round a loop of {len} steps from step {start}")
+            }
+        }
+    }
+}
+
+fn facing(c: char) -> Option<Direction> {
+    match c {
+        '^' => Some(Direction::Up),
+        '>' => Some(Direction::Right),
+        'v' => Some(Direction::Down),
+        '<' => Some(Direction::Left),
+        _ => None,
+    }
+}
+
+fn symbol(facing: Direction) -> char {
+    match facing {
+        Direction::Up => '^',
+        Direction::Right => '>',
+        Direction::Down => 'v',
+        Direction::Left => '<',
+    }
+}
+
+/// The obstacles sorted along every row and column, so the guard can jump straight from one to
+/// the next.
+#[derive(Debug, Clone)]
+struct Obstacles {
+    /// The x of every obstacle, by row
+    rows: Vec<Vec<i32>>,
+    /// The y of every obstacle, by column
+    columns: Vec<Vec<i32>>,
+}
+
+impl Obstacles {
+    fn new(width: i32, height: i32, obstacles: impl IntoIterator<Item = Pos>) -> Self {
+        let mut rows = vec![Vec::new(); height as usize];
+        let mut columns = vec![Vec::new(); width as usize];
+        for pos in obstacles {
+            rows[pos.y as usize].push(pos.x);
+            columns[pos.x as usize].push(pos.y);
+        }
+        rows.iter_mut().for_each(|row| row.sort_unstable());
+        columns.iter_mut().for_each(|column| column.sort_unstable());
+        Obstacles { rows, columns }
+    }
+
+    fn contains(&self, pos: Pos) -> bool {
+        self.rows
+            .get(pos.y as usize)
+            .is_some_and(|row| row.binary_search(&pos.x).is_ok())
+    }
+
+    /// The nearest obstacle ahead of `pos` looking in `dir`, counting `extra` as one too.
+    fn ahead(&self, pos: Pos, dir: Direction, extra: Option<Pos>) -> Option<Pos> {
+        let (line, along) = match dir {
+            Direction::Up | Direction::Down => (&self.columns[pos.x as usize], pos.y),
+            Direction::Left | Direction::Right => (&self.rows[pos.y as usize], pos.x),
+        };
+        let idx = line.partition_point(|n| *n < along);
+        let next = match dir {
+            Direction::Up | Direction::Left => idx.checked_sub(1).map(|idx| line[idx]),
+            Direction::Down | Direction::Right => line[idx..].iter().find(|n| **n > along).copied(),
+        };
+        let found = next.map(|n| match dir {
+            Direction::Up | Direction::Down => Pos::new(pos.x, n),
+            Direction::Left | Direction::Right => Pos::new(n, pos.y),
+        });
+
+        // The extra obstacle is in the way if it's ahead and no further than the one found
+        let Some(extra) = extra else {
+            return found;
+        };
+        let distance = |to: Pos| {
+            let offset = to - pos;
+            let step = dir.offset();
+            let n = offset.x * step.x + offset.y * step.y;
+            (n > 0 && step * n == offset).then_some(n)
+        };
+        match (distance(extra), found) {
+            (Some(n), Some(found)) if distance(found).is_some_and(|m| m < n) => Some(found),
+            (Some(_), _) => Some(extra),
+            (None, found) => found,
         }
     }
 }
 
 #[derive(Debug, Clone)]
 struct Area {
-    obstacles: HashSet<Pos>,
-    guard_position: Pos,
+    obstacles: Obstacles,
+    /// In the order they're on the map, row by row
+    guards: Vec<Guard>,
     width: i32,
     height: i32,
 }
 
 impl FromStr for Area {
-    type Err = anyhow::Error;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let iter = s.split_whitespace().enumerate().flat_map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(move |(x, c)| (Pos::new(x as _, y as _), c))
-        });
+        let grid = Grid::parse_with(s, |c| {
+            (matches!(c, '.' | '#') || facing(c).is_some()).then_some(c)
+        })
+        .map_err(|err| err.locate(s))?;
 
-        let guard_position = iter
-            .clone()
-            .find(|(_, c)| *c == '^')
-            .ok_or(anyhow!("parse error"))?
-            .0;
-
-        let obstacles: HashSet<Pos> = iter
-            .clone()
-            .filter_map(|(pos, c)| match c {
-                '#' => Some(pos),
-                _ => None,
+        let guards: Vec<Guard> = grid
+            .iter()
+            .filter_map(|(pos, c)| {
+                Some(Guard {
+                    pos,
+                    facing: facing(*c)?,
+                })
             })
             .collect();
+        if guards.is_empty() {
+            return Err(ParseError::at_end(
+                s,
+                "no guard `^`, `>`, `v` or `<` on the map",
+            ));
+        }
 
-        let (max_pos, _) = iter.max().ok_or(anyhow!("parse error"))?;
+        let (width, height) = (grid.width() as i32, grid.height() as i32);
+        let obstacles = grid.iter().filter_map(|(pos, c)| match c {
+            '#' => Some(pos),
+            _ => None,
+        });
 
         Ok(Self {
-            obstacles,
-            guard_position,
-            width: max_pos.x + 1,
-            height: max_pos.y + 1,
+            obstacles: Obstacles::new(width, height, obstacles),
+            guards,
+            width,
+            height,
         })
     }
 }
 
 impl Area {
-    fn guard_walk(&self) -> impl Iterator<Item = (Pos, Direction)> + '_ {
-        let mut pos = self.guard_position;
-        let mut dir = Direction::Up;
-        std::iter::once((self.guard_position, dir.clone())).chain(std::iter::from_fn(move || {
-            while self.obstacles.contains(&pos.walk(dir)) {
+    fn contains(&self, pos: Pos) -> bool {
+        pos.x < self.width && pos.y < self.height && pos.x >= 0 && pos.y >= 0
+    }
+
+    /// Where `guard` goes, cell by cell, until they leave the area or are about to go round a
+    /// loop again.
+    fn guard_walk(&self, guard: Guard) -> impl Iterator<Item = (Pos, Direction)> + '_ {
+        let Guard {
+            mut pos,
+            facing: mut dir,
+        } = guard;
+        let mut seen = HashSet::from([(pos, dir)]);
+        std::iter::once((pos, dir)).chain(std::iter::from_fn(move || {
+            let mut turns = 0;
+            while self.obstacles.contains(pos.walk(dir)) {
                 dir = dir.turn_right();
+                turns += 1;
+                if turns == 4 {
+                    // Boxed in
+                    return None;
+                }
             }
             pos = pos.walk(dir);
-            if pos.x < self.width && pos.y < self.height && pos.x >= 0 && pos.y >= 0 {
-                Some((pos, dir))
+            (self.contains(pos) && seen.insert((pos, dir))).then_some((pos, dir))
+        }))
+    }
+
+    /// Follows `guard` step by step to the end of their patrol.
+    fn patrol(&self, mut guard: Guard) -> Outcome {
+        let mut seen = HashMap::from([(guard, 0)]);
+        for step in 1.. {
+            let ahead = guard.pos.walk(guard.facing);
+            if self.obstacles.contains(ahead) {
+                guard.facing = guard.facing.turn_right();
+            } else if self.contains(ahead) {
+                guard.pos = ahead;
             } else {
-                None
+                return Outcome::Exits {
+                    edge: guard.facing,
+                    step,
+                };
             }
-        }))
+            if let Some(start) = seen.insert(guard, step) {
+                return Outcome::Loops {
+                    start,
+                    len: step - start,
+                };
+            }
+        }
+        unreachable!()
     }
 
-    fn guard_walk_will_loop(&self) -> bool {
-        let mut set = HashSet::new();
-        self.guard_walk().any(|(pos, dir)| !set.insert((pos, dir)))
+    /// How every guard's patrol ends, a line each.
+    fn report(&self) -> String {
+        self.guards
+            .iter()
+            .map(|guard| {
+                let Pos { x, y } = guard.pos;
+                let facing = symbol(guard.facing);
+                format!("{facing} at ({x}, {y}) {}\n", self.patrol(*guard))
+            })
+            .collect()
     }
 
-    fn possible_new_obstacle_positions(&self) -> impl Iterator<Item = Pos> + '_ {
-        self.guard_walk()
-            .map(|(pos, _)| pos)
-            .filter(|pos| *pos != self.guard_position)
-            .collect::<HashSet<_>>()
-            .into_iter()
+    fn render(&self, visited: &HashSet<Pos>, guard: Pos, facing: Direction) -> String {
+        let max = Pos::new(self.width - 1, self.height - 1);
+        aoc_grid::render(Pos::new(0, 0), max, |p| match p {
+            _ if p == guard => symbol(facing),
+            _ if self.obstacles.contains(p) => '#',
+            _ if visited.contains(&p) => 'X',
+            _ => '.',
+        })
+    }
+
+    /// Whether the guard, standing at `pos` facing `dir`, walks in a loop with an obstacle added
+    /// at `extra`. Goes from obstacle to obstacle rather than cell by cell.
+    fn guard_walk_will_loop(&self, mut pos: Pos, mut dir: Direction, extra: Pos) -> bool {
+        let mut turns = HashSet::new();
+        while let Some(obstacle) = self.obstacles.ahead(pos, dir, Some(extra)) {
+            pos = obstacle - dir.offset();
+            if !turns.insert((pos, dir)) {
+                return true;
+            }
+            dir = dir.turn_right();
+        }
+        false
+    }
+
+    /// Every cell on `guard`'s path where a new obstacle could go, with where they stand and
+    /// face just before first walking into it.
+    fn possible_new_obstacle_positions(&self, guard: Guard) -> HashMap<Pos, (Pos, Direction)> {
+        let mut candidates = HashMap::new();
+        let mut before = None;
+        for (pos, dir) in self.guard_walk(guard) {
+            if let Some(prev) = before {
+                if pos != guard.pos {
+                    candidates.entry(pos).or_insert((prev, dir));
+                }
+            }
+            before = Some(pos);
+        }
+        candidates
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let area = Area::from_str(input)?;
+    let mut visited = HashSet::new();
+    for guard in &area.guards {
+        for (pos, dir) in area.guard_walk(*guard) {
+            visited.insert(pos);
+            visualize::frame(|| area.render(&visited, pos, dir));
+        }
+    }
+    Ok(visited.len())
 }
 
-impl Direction {
-    fn turn_right(&self) -> Self {
-        match self {
-            Self::Up => Self::Right,
-            Self::Right => Self::Down,
-            Self::Down => Self::Left,
-            Self::Left => Self::Up,
+/// Counts the places in a guard's way where a new obstacle would send them round a loop. It can't
+/// go where any guard starts.
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let area = Area::from_str(input)?;
+    let starts: HashSet<Pos> = area.guards.iter().map(|guard| guard.pos).collect();
+    // Where each guard stands and faces just before first walking into the new obstacle
+    let mut candidates: HashMap<Pos, Vec<(Pos, Direction)>> = HashMap::new();
+    for guard in &area.guards {
+        for (obstacle, approach) in area.possible_new_obstacle_positions(*guard) {
+            if !starts.contains(&obstacle) {
+                candidates.entry(obstacle).or_default().push(approach);
+            }
         }
     }
+    let candidates: Vec<_> = candidates.into_iter().collect();
+    Ok(parallel::count(&candidates, |(obstacle, approaches)| {
+        approaches
+            .iter()
+            .any(|(pos, dir)| area.guard_walk_will_loop(*pos, *dir, *obstacle))
+    }))
+}
+
+/// How every guard's patrol on the map ends, a line each in the order they're on the map.
+pub fn patrol_report(input: &str) -> anyhow::Result<String> {
+    Ok(Area::from_str(input)?.report())
 }
 
-pub fn main() -> anyhow::Result<()> {
-    let area = {
-        let mut s = String::new();
-        stdin().read_to_string(&mut s)?;
-        Area::from_str(&s)?
-    };
-    let part_1 = area
-        .guard_walk()
-        .map(|(pos, _)| pos)
-        .collect::<HashSet<_>>()
-        .len();
-    println!("part 1: {part_1}");
-
-    let part_2 = area
-        .possible_new_obstacle_positions()
-        .map(|pos| {
-            let mut a = area.clone();
-            a.obstacles.insert(pos);
-            a
+pub const DAY: aoc_common::Day = aoc_common::day!(2024, 6, parse: Area::from_str);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 41, part_2: 6 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Part 2 the straightforward way, walking the guards' whole paths again cell by cell for
+    /// every new obstacle.
+    fn count_loops(area: &Area) -> usize {
+        let loops = |guard: Guard, extra: Pos| {
+            let Guard {
+                mut pos,
+                facing: mut dir,
+            } = guard;
+            let mut seen = HashSet::new();
+            while seen.insert((pos, dir)) {
+                let next = pos.walk(dir);
+                if next == extra || area.obstacles.contains(next) {
+                    dir = dir.turn_right();
+                } else if area.contains(next) {
+                    pos = next;
+                } else {
+                    return false;
+                }
+            }
+            true
+        };
+        // Only an obstacle in a guard's way changes where they go
+        let starts: HashSet<Pos> = area.guards.iter().map(|guard| guard.pos).collect();
+        let trapping: HashSet<Pos> = area
+            .guards
+            .iter()
+            .flat_map(|guard| {
+                let path: HashSet<Pos> = area.guard_walk(*guard).map(|(pos, _)| pos).collect();
+                path.into_iter()
+                    .filter(|pos| !starts.contains(pos))
+                    .filter(move |extra| loops(*guard, *extra))
+            })
+            .collect();
+        trapping.len()
+    }
+
+    /// A map of the given size with one to three guards somewhere facing any way, and obstacles
+    /// about one cell in `sparsity`.
+    fn map(width: usize, height: usize, sparsity: u32) -> impl Strategy<Value = String> {
+        let cells = prop::collection::vec(0..sparsity, width * height);
+        let guard = (0..width * height, prop::sample::select(&Direction::ALL[..]));
+        let guards = prop::collection::vec(guard, 1..4);
+        (cells, guards).prop_map(move |(cells, guards)| {
+            let mut map = String::new();
+            for (idx, cell) in cells.iter().enumerate() {
+                let guard = guards.iter().find(|(guard, _)| *guard == idx);
+                map.push(match (guard, *cell) {
+                    (Some((_, facing)), _) => symbol(*facing),
+                    (None, 0) => '#',
+                    (None, _) => '.',
+                });
+                if idx % width == width - 1 {
+                    map.push('\n');
+                }
+            }
+            map
         })
-        .filter(|a| a.guard_walk_will_loop())
-        .count();
+    }
 
-    println!("part 2: {part_2}");
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(map in (1..16usize, 1..16usize, 2..8u32)
+            .prop_flat_map(|(width, height, sparsity)| map(width, height, sparsity)))
+        {
+            let area = map.parse::<Area>().unwrap();
+            let guards: HashMap<Pos, Direction> =
+                area.guards.iter().map(|guard| (guard.pos, guard.facing)).collect();
+            let max = Pos::new(area.width - 1, area.height - 1);
+            let rendered = aoc_grid::render(Pos::new(0, 0), max, |pos| match guards.get(&pos) {
+                Some(facing) => symbol(*facing),
+                None if area.obstacles.contains(pos) => '#',
+                None => '.',
+            });
+            prop_assert_eq!(rendered + "\n", map);
+        }
+
+        #[test]
+        fn test_jumping_matches_walking(map in (1..16usize, 1..16usize, 2..8u32)
+            .prop_flat_map(|(width, height, sparsity)| map(width, height, sparsity)))
+        {
+            let area = map.parse::<Area>().unwrap();
+            prop_assert_eq!(part_2(&map).unwrap(), count_loops(&area));
+        }
+
+        #[test]
+        fn test_patrol_matches_walk(map in (1..16usize, 1..16usize, 2..8u32)
+            .prop_flat_map(|(width, height, sparsity)| map(width, height, sparsity)))
+        {
+            let area = map.parse::<Area>().unwrap();
+            let guard = area.guards[0];
+            let (last, _) = area.guard_walk(guard).last().unwrap();
+            match area.patrol(guard) {
+                Outcome::Exits { edge, .. } => prop_assert!(!area.contains(last.walk(edge))),
+                Outcome::Loops { len, .. } => prop_assert!(len >= 4),
+            }
+        }
+    }
+
+    #[test]
+    fn test_patrol() {
+        let patrols = [
+            (
+                "..\n^.\n",
+                "^ at (0, 1) leaves across the top edge on step 2\n",
+            ),
+            (
+                ".#...\n....#\n.....\n#....\n.^.#.\n",
+                "^ at (1, 4) goes round a loop of 12 steps from step 1\n",
+            ),
+            (
+                ".#.\n#>#\n.#.\n",
+                "> at (1, 1) goes round a loop of 4 steps from step 0\n",
+            ),
+            (
+                "v..<\n..#.\n",
+                "v at (0, 0) leaves across the bottom edge on step 2\n\
+                 < at (3, 0) leaves across the left edge on step 4\n",
+            ),
+        ];
+        for (map, report) in patrols {
+            assert_eq!(map.parse::<Area>().unwrap().report(), report, "{map}");
+        }
+    }
+
+    #[test]
+    fn test_patrol_report() {
+        // Reported in the order the guards are on the map, row by row
+        assert_eq!(
+            patrol_report(".#.v.\n#>#..\n.#..^\n").unwrap(),
+            "v at (3, 0) leaves across the bottom edge on step 3\n\
+             > at (1, 1) goes round a loop of 4 steps from step 0\n\
+             ^ at (4, 2) leaves across the top edge on step 3\n"
+        );
+        assert!(patrol_report("...\n").is_err());
+    }
+
+    #[test]
+    fn test_guards() {
+        // Guards don't get in each other's way, part 1 counts where any of them goes
+        assert_eq!(part_1("v..<\n..#.\n").unwrap(), 5);
+        assert_eq!(part_1(">...\n.#..\n").unwrap(), 4);
+        assert_eq!(part_1(".#.v.\n#>#..\n.#..^\n").unwrap(), 7);
+        // Part 2 counts where a new obstacle traps any of them, but not where one stands
+        let map = include_str!("example.txt").replace("#.........", "#..>......");
+        assert_eq!(part_2(&map).unwrap(), 5);
+        assert_eq!(count_loops(&map.parse().unwrap()), 5);
+        let err = "...\n.#.\n".parse::<Area>().unwrap_err();
+        assert_eq!(err.message, "no guard `^`, `>`, `v` or `<` on the map");
+        let err = ".^.\n.x.\n".parse::<Area>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
 
-    Ok(())
+    #[test]
+    fn test_ahead() {
+        let area = "..#..\n.....\n#.^.#\n.....\n..#..\n"
+            .parse::<Area>()
+            .unwrap();
+        let obstacles = &area.obstacles;
+        let guard = area.guards[0].pos;
+        let ahead = |dir, extra| obstacles.ahead(guard, dir, extra);
+        assert_eq!(ahead(Direction::Up, None), Some(Pos::new(2, 0)));
+        assert_eq!(ahead(Direction::Right, None), Some(Pos::new(4, 2)));
+        assert_eq!(ahead(Direction::Down, None), Some(Pos::new(2, 4)));
+        assert_eq!(ahead(Direction::Left, None), Some(Pos::new(0, 2)));
+        // Only an extra obstacle in front of the others gets in the way
+        let extra = Some(Pos::new(2, 1));
+        assert_eq!(ahead(Direction::Up, extra), Some(Pos::new(2, 1)));
+        assert_eq!(ahead(Direction::Down, extra), Some(Pos::new(2, 4)));
+        assert_eq!(
+            ahead(Direction::Right, Some(Pos::new(5, 2))),
+            Some(Pos::new(4, 2))
+        );
+        assert_eq!(obstacles.ahead(Pos::new(1, 1), Direction::Up, None), None);
+        assert_eq!(
+            obstacles.ahead(Pos::new(1, 1), Direction::Up, Some(Pos::new(1, 0))),
+            Some(Pos::new(1, 0))
+        );
+    }
+
+    /// A map where the guard spirals inwards, along legs `len` long and two cells apart.
+    fn spiral(len: i32) -> String {
+        let size = len + 3;
+        let mut pos = Pos::new(1, len + 1);
+        let mut obstacles = HashSet::new();
+        let legs = [len, len]
+            .into_iter()
+            .chain((0..).map(|n| len - 2 * ((n + 1) / 2)));
+        let mut dir = Direction::Up;
+        for leg in legs.take_while(|leg| *leg > 0) {
+            pos += dir.offset() * leg;
+            obstacles.insert(pos.walk(dir));
+            dir = dir.turn_right();
+        }
+        let guard = Pos::new(1, len + 1);
+        aoc_grid::render(Pos::new(0, 0), Pos::new(size - 1, size - 1), |p| match p {
+            _ if p == guard => '^',
+            _ if obstacles.contains(&p) => '#',
+            _ => '.',
+        }) + "\n"
+    }
+
+    #[test]
+    fn test_spiral() {
+        let map = spiral(40);
+        let area = map.parse::<Area>().unwrap();
+        assert!(area.guard_walk(area.guards[0]).count() > 40 * 40 / 2);
+        assert_eq!(part_2(&map).unwrap(), count_loops(&area));
+    }
 }
@@ -1,8 +1,8 @@
-use anyhow::anyhow;
-use itertools::Itertools;
-use std::{io::stdin, str::FromStr};
+use anyhow::{anyhow, bail};
+use aoc_common::parallel;
+use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 struct OperatorlessEquation {
     numbers: Vec<i64>,
     value: i64,
@@ -11,14 +11,21 @@ struct OperatorlessEquation {
 impl FromStr for OperatorlessEquation {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(':');
-        let value = iter.next().ok_or(anyhow!("parse error"))?.parse()?;
-        let numbers = iter
-            .next()
-            .ok_or(anyhow!("parse error"))?
+        let (value, numbers) = s
+            .split_once(':')
+            .ok_or(anyhow!("expected an equation like `190: 10 19`"))?;
+        let value = value.parse()?;
+        let numbers = numbers
             .split_whitespace()
             .map(|s| s.parse())
             .collect::<Result<Vec<_>, _>>()?;
+        if numbers.is_empty() {
+            bail!("expected at least one number after `:`");
+        }
+        // Concatenating only makes sense for numbers written without a sign
+        if value < 0 || numbers.iter().any(|n| *n < 0) {
+            bail!("the numbers can't be negative");
+        }
         Ok(Self { value, numbers })
     }
 }
@@ -30,55 +37,129 @@ enum Operator {
     Concat,
 }
 
+impl Operator {
+    /// The result of the operator, or `None` if it doesn't fit in an `i64`.
+    fn apply(self, acc: i64, n: i64) -> Option<i64> {
+        match self {
+            Operator::Add => acc.checked_add(n),
+            Operator::Mul => acc.checked_mul(n),
+            Operator::Concat => {
+                let digits_in_n = n.checked_ilog10().map_or(1, |log| log + 1);
+                acc.checked_mul(10i64.checked_pow(digits_in_n)?)?
+                    .checked_add(n)
+            }
+        }
+    }
+}
+
 impl OperatorlessEquation {
-    pub fn can_be_true<T, IT>(&self, valid_operators: T) -> bool
-    where
-        T: IntoIterator<Item = Operator, IntoIter = IT>,
-        IT: Iterator<Item = Operator> + Clone,
-    {
-        let operator_possibilities =
-            itertools::repeat_n(valid_operators.into_iter(), self.numbers.len() - 1)
-                .multi_cartesian_product();
-
-        operator_possibilities
-            .map(|operators| {
-                operators.iter().zip(self.numbers.iter().skip(1)).fold(
-                    self.numbers[0],
-                    |acc, (op, n)| match op {
-                        Operator::Add => acc + (*n),
-                        Operator::Mul => acc * (*n),
-                        Operator::Concat => {
-                            let digits_in_n = f64::log10(*n as f64).floor() as u32 + 1;
-                            acc * i64::pow(10, digits_in_n) + n
-                        }
-                    },
-                )
-            })
-            .any(|v| self.value == v)
+    /// Tries every way of putting `valid_operators` between the numbers. The ways are numbered
+    /// so they can be split between threads: the digits of the number in base
+    /// `valid_operators.len()` pick the operator for each gap.
+    pub fn can_be_true(&self, valid_operators: &[Operator]) -> anyhow::Result<bool> {
+        let gaps = self.numbers.len() as u32 - 1;
+        let Some(possibilities) = valid_operators.len().checked_pow(gaps) else {
+            bail!(
+                "{} numbers are too many to try every way of putting operators between",
+                self.numbers.len()
+            );
+        };
+
+        Ok(parallel::any(0..possibilities, |mut possibility| {
+            let value = self.numbers[1..]
+                .iter()
+                .try_fold(self.numbers[0], |acc, n| {
+                    let op = valid_operators[possibility % valid_operators.len()];
+                    possibility /= valid_operators.len();
+                    op.apply(acc, *n)
+                });
+            value == Some(self.value)
+        }))
     }
 }
 
-fn main() -> anyhow::Result<()> {
-    let equations = stdin()
-        .lines()
-        .map(|s| s?.parse())
-        .collect::<Result<Vec<OperatorlessEquation>, _>>()?;
+/// The total of the equations that can be made true with `valid_operators`.
+fn calibration(input: &str, valid_operators: &[Operator]) -> anyhow::Result<i64> {
+    let mut total = 0;
+    for equation in parse(input)? {
+        if equation.can_be_true(valid_operators)? {
+            total += equation.value;
+        }
+    }
+    Ok(total)
+}
 
-    let part_1: i64 = equations
-        .iter()
-        .filter(|x| x.can_be_true([Operator::Add, Operator::Mul]))
-        .map(|x| x.value)
-        .sum();
+fn parse(input: &str) -> Result<Vec<OperatorlessEquation>, aoc_common::ParseError> {
+    aoc_common::parse_lines(input, str::parse)
+}
 
-    println!("part 1: {part_1}");
+pub fn part_1(input: &str) -> anyhow::Result<i64> {
+    calibration(input, &[Operator::Add, Operator::Mul])
+}
 
-    let part_2: i64 = equations
-        .iter()
-        .filter(|x| x.can_be_true([Operator::Add, Operator::Mul, Operator::Concat]))
-        .map(|x| x.value)
-        .sum();
+pub fn part_2(input: &str) -> anyhow::Result<i64> {
+    calibration(input, &[Operator::Add, Operator::Mul, Operator::Concat])
+}
 
-    println!("part 2: {part_2}");
+pub const DAY: aoc_common::Day = aoc_common::day!(2024, 7, parse: parse);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 3749, part_2: 11387 },
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn equation() -> impl Strategy<Value = OperatorlessEquation> {
+        (0..i64::MAX, prop::collection::vec(1..1000i64, 1..12))
+            .prop_map(|(value, numbers)| OperatorlessEquation { value, numbers })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(equations in prop::collection::vec(equation(), 0..10)) {
+            let input: String = equations
+                .iter()
+                .map(|e| {
+                    let numbers: Vec<String> = e.numbers.iter().map(i64::to_string).collect();
+                    format!("{}: {}\n", e.value, numbers.join(" "))
+                })
+                .collect();
+            prop_assert_eq!(parse(&input).unwrap(), equations);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("190: 10 19\n3267 81 40 27\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(parse("190: 10 x\n").is_err());
+        assert!(parse("99999999999999999999: 1\n").is_err());
+        assert!(parse("190:\n").is_err());
+        let err = parse("190: 10 -19\n").unwrap_err();
+        assert_eq!(err.message, "the numbers can't be negative");
+        assert!(parse("-190: 10 19\n").is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Too many gaps to number every way of filling them
+        let long = format!("1: {}\n", ["1"; 70].join(" "));
+        assert!(part_1(&long).is_err());
+        // Results past `i64::MAX` just don't match
+        let big = format!("{}: {} 10 10\n", i64::MAX, i64::MAX / 2);
+        assert_eq!(part_2(&big).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_concat() {
+        assert_eq!(Operator::Concat.apply(12, 345), Some(12345));
+        assert_eq!(Operator::Concat.apply(1, 0), Some(10));
+        // Where a float's logarithm rounds up to the next power of 10
+        let n = 999_999_999_999_999;
+        assert_eq!(Operator::Concat.apply(1, n), Some(1_999_999_999_999_999));
+        assert_eq!(Operator::Concat.apply(10, i64::MAX), None);
+    }
 }
@@ -1,53 +1,12 @@
-use anyhow::anyhow;
+use aoc_common::ParseError;
+use aoc_grid::{Grid, Pos};
 use itertools::*;
-use std::{
-    collections::HashSet,
-    io::{stdin, Read},
-    ops::{Add, Mul, Sub},
-    str::FromStr,
-};
-
-#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
-struct Pos {
-    x: i32,
-    y: i32,
-}
-
-impl Add for Pos {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        Pos {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl Sub for Pos {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self::Output {
-        Pos {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
-}
-
-impl Mul<i32> for Pos {
-    type Output = Self;
-    fn mul(self, rhs: i32) -> Self::Output {
-        Pos {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
-    }
-}
+use std::{collections::HashSet, str::FromStr};
 
 #[derive(Debug)]
 struct Antennas {
     groups: Vec<AntennaGroup>,
-    width: i32,
-    height: i32,
+    map: Grid<char>,
 }
 
 impl Antennas {
@@ -55,14 +14,14 @@ impl Antennas {
         self.groups
             .iter()
             .flat_map(|group| group.antinodes(2))
-            .filter(|pos| pos.x >= 0 && pos.x < self.width && pos.y >= 0 && pos.y < self.height)
+            .filter(|pos| self.map.contains(*pos))
     }
 
     pub fn antinodes_with_resonant_harmonics(&self) -> impl Iterator<Item = Pos> + '_ {
-        let n = i32::max(self.width, self.height);
+        let n = usize::max(self.map.width(), self.map.height()) as i32;
         (0..n)
             .flat_map(|n| self.groups.iter().flat_map(move |group| group.antinodes(n)))
-            .filter(|pos| pos.x >= 0 && pos.x < self.width && pos.y >= 0 && pos.y < self.height)
+            .filter(|pos| self.map.contains(*pos))
     }
 }
 
@@ -84,23 +43,13 @@ impl AntennaGroup {
 }
 
 impl FromStr for Antennas {
-    type Err = anyhow::Error;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let groups = s
-            .lines()
-            .enumerate()
-            .flat_map(|(y, s)| {
-                s.chars().enumerate().filter_map(move |(x, c)| match c {
-                    '.' => None,
-                    c => Some((
-                        c,
-                        Pos {
-                            x: x as _,
-                            y: y as _,
-                        },
-                    )),
-                })
-            })
+        let map = s.parse::<Grid<char>>().map_err(|err| err.locate(s))?;
+        let groups = map
+            .iter()
+            .filter(|(_, c)| **c != '.')
+            .map(|(pos, c)| (*c, pos))
             .sorted()
             .fold(Vec::new(), |mut acc: Vec<AntennaGroup>, (c, pos)| {
                 match acc.last_mut() {
@@ -114,31 +63,69 @@ impl FromStr for Antennas {
                 };
                 acc
             });
-        let height = s.lines().count() as i32;
-        let width = s.lines().next().ok_or(anyhow!("parse error"))?.len() as i32;
-        Ok(Self {
-            groups,
-            width,
-            height,
-        })
+        Ok(Self { groups, map })
     }
 }
 
-pub fn main() -> anyhow::Result<()> {
-    let antennas = {
-        let mut s = String::new();
-        stdin().read_to_string(&mut s)?;
-        s.parse::<Antennas>()?
-    };
+pub fn part_1(input: &str) -> anyhow::Result<usize> {
+    let antennas = input.parse::<Antennas>()?;
     let antinodes = antennas.antinodes().collect::<HashSet<_>>();
-    println!("Part 1: {}", antinodes.len());
+    Ok(antinodes.len())
+}
 
+pub fn part_2(input: &str) -> anyhow::Result<usize> {
+    let antennas = input.parse::<Antennas>()?;
     let antinodes = antennas
         .antinodes_with_resonant_harmonics()
         .collect::<HashSet<_>>();
 
     // println!("{antinodes:#?}");
 
-    println!("Part 2: {}", antinodes.len());
-    Ok(())
+    Ok(antinodes.len())
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(2024, 8, parse: Antennas::from_str);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 14, part_2: 34 },
+    "example_2.txt" => { part_2: 9 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    /// The rows of a map of any size, with antennas of a few frequencies.
+    fn rows() -> impl Strategy<Value = Vec<String>> {
+        (1..10usize, 1..10usize).prop_flat_map(|(width, height)| {
+            let row = prop::string::string_regex(&format!("[.aA0]{{{width}}}")).unwrap();
+            prop::collection::vec(row, height)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(rows in rows()) {
+            let input: String = rows.iter().map(|row| format!("{row}\n")).collect();
+            let antennas = input.parse::<Antennas>().unwrap();
+            prop_assert_eq!(antennas.map.to_string(), rows.join("\n"));
+
+            let mut expected: HashMap<char, HashSet<Pos>> = HashMap::new();
+            for (y, row) in rows.iter().enumerate() {
+                for (x, c) in row.chars().enumerate().filter(|(_, c)| *c != '.') {
+                    expected.entry(c).or_default().insert(Pos::new(x as i32, y as i32));
+                }
+            }
+            let ids = antennas.groups.iter().map(|group| group.id);
+            prop_assert!(ids.tuple_windows().all(|(a, b)| a < b));
+            let groups: HashMap<char, HashSet<Pos>> = antennas
+                .groups
+                .into_iter()
+                .map(|group| (group.id, group.positions))
+                .collect();
+            prop_assert_eq!(groups, expected);
+        }
+    }
 }
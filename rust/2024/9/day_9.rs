@@ -1,218 +1,1085 @@
-use anyhow::anyhow;
-use itertools::*;
+use aoc_common::{visualize, Param, ParseError};
+use itertools::Itertools;
 use std::{
-    io::{stdin, Read},
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    ops::Range,
     str::FromStr,
 };
 
-#[derive(Debug, Clone, Copy)]
-struct Free {
-    size: i32,
+const MAP_FORMAT: Param = Param {
+    name: "map-format",
+    default: "dense",
+    help: "How the disk map is written: dense, spans or layout",
+};
+
+const PART_1_COMPACTION: Param = Param {
+    name: "part-1-compaction",
+    default: "blocks",
+    help: "How part 1 compacts the disk, see part-2-compaction",
+};
+
+const PART_2_COMPACTION: Param = Param {
+    name: "part-2-compaction",
+    default: "first-fit",
+    help: "How part 2 compacts the disk: blocks, first-fit, best-fit, worst-fit, next-fit \
+        or defragment",
+};
+
+/// The most blocks a disk can have, which keeps a block by block copy like [`Disk::blocks`] to a
+/// few hundred megabytes.
+const MAX_BLOCKS: usize = 1 << 24;
+
+/// The size of a disk of `size` blocks after adding a span of `len`, unless that's too big.
+fn grow(size: usize, len: usize) -> Result<usize, String> {
+    if len > MAX_BLOCKS {
+        return Err(format!("a span can't be more than {MAX_BLOCKS} blocks"));
+    }
+    size.checked_add(len)
+        .filter(|size| *size <= MAX_BLOCKS)
+        .ok_or_else(|| format!("a disk can't be more than {MAX_BLOCKS} blocks"))
+}
+
+/// The ways of writing down a disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// The puzzle's disk map, a digit per span, alternating between files numbered from 0 and
+    /// free space
+    Dense,
+    /// Spans separated by whitespace, `ID:LEN` for a file and `.:LEN` for free space, as [`Disk`]
+    /// displays
+    Spans,
+    /// A character per block, the id of the file it belongs to or `.`, as [`Disk::render`] draws
+    /// disks with fewer than ten files
+    Layout,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dense" => Ok(Format::Dense),
+            "spans" => Ok(Format::Spans),
+            "layout" => Ok(Format::Layout),
+            _ => Err("expected dense, spans or layout".to_owned()),
+        }
+    }
+}
+
+/// Which of the free spaces a file fits in to move it to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fit {
+    /// The leftmost
+    First,
+    /// The smallest
+    Best,
+    /// The largest
+    Worst,
+    /// The leftmost after where the previous file went, starting over from the left of the disk
+    /// when there's none
+    Next,
+}
+
+/// How to compact a disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compaction {
+    /// See [`Disk::compact`]
+    Blocks,
+    /// See [`Disk::compact_defragged`]
+    Files(Fit),
+    /// See [`Disk::defragment`]
+    Defragment,
+}
+
+impl FromStr for Compaction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blocks" => Ok(Compaction::Blocks),
+            "first-fit" => Ok(Compaction::Files(Fit::First)),
+            "best-fit" => Ok(Compaction::Files(Fit::Best)),
+            "worst-fit" => Ok(Compaction::Files(Fit::Worst)),
+            "next-fit" => Ok(Compaction::Files(Fit::Next)),
+            "defragment" => Ok(Compaction::Defragment),
+            _ => Err(
+                "expected blocks, first-fit, best-fit, worst-fit, next-fit or defragment"
+                    .to_owned(),
+            ),
+        }
+    }
+}
+
+/// What compacting a disk did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Stats {
+    /// How many times a file or a piece of one was moved
+    moves: usize,
+    blocks_moved: usize,
+    /// See [`Disk::fragmentation`]
+    fragmentation: usize,
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} moves, {} blocks moved, fragmentation {}",
+            self.moves, self.blocks_moved, self.fragmentation
+        )
+    }
+}
+
+/// A run of consecutive blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Span {
+    start: usize,
+    len: usize,
+}
+
+impl Span {
+    fn end(&self) -> usize {
+        self.start + self.len
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct File {
-    id: i32,
-    size: i32,
+    id: usize,
+    /// Where the file's blocks are, in disk order. Files start out in one piece, compacting block
+    /// by block can split them up.
+    spans: Vec<Span>,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Cell {
-    Free(Free),
-    File(File),
+impl File {
+    fn start(&self) -> usize {
+        self.spans[0].start
+    }
+
+    fn len(&self) -> usize {
+        self.spans.iter().map(|s| s.len).sum()
+    }
+}
+
+/// The free space on a disk, as runs of free blocks that are merged whenever they touch.
+#[derive(Debug, Clone)]
+struct FreeSpace {
+    /// The length of every run, by where it starts
+    runs: BTreeMap<usize, usize>,
+    /// Where the runs of each length start, for only the lengths some run has
+    starts_by_len: BTreeMap<usize, BTreeSet<usize>>,
+    /// How many lengths the searches have looked at, to keep an eye on how the work grows with
+    /// the size of the disk
+    #[cfg(test)]
+    lookups: usize,
+}
+
+impl FreeSpace {
+    /// Free space made of `runs`, which are in order and don't touch.
+    fn from_runs(runs: Vec<Span>) -> Self {
+        let runs: Vec<Span> = runs.into_iter().filter(|run| run.len > 0).collect();
+        let mut starts: Vec<(usize, usize)> = runs.iter().map(|run| (run.len, run.start)).collect();
+        starts.sort_unstable();
+        FreeSpace {
+            runs: runs.iter().map(|run| (run.start, run.len)).collect(),
+            starts_by_len: starts
+                .chunk_by(|(a, _), (b, _)| a == b)
+                .map(|runs| (runs[0].0, runs.iter().map(|(_, start)| *start).collect()))
+                .collect(),
+            #[cfg(test)]
+            lookups: 0,
+        }
+    }
+
+    fn insert(&mut self, span: Span) {
+        if span.len > 0 {
+            self.runs.insert(span.start, span.len);
+            let starts = self.starts_by_len.entry(span.len).or_default();
+            starts.insert(span.start);
+        }
+    }
+
+    /// Removes the run starting at `start`, returning its length if there was one.
+    fn remove(&mut self, start: usize) -> Option<usize> {
+        let len = self.runs.remove(&start)?;
+        if let Some(starts) = self.starts_by_len.get_mut(&len) {
+            starts.remove(&start);
+            if starts.is_empty() {
+                self.starts_by_len.remove(&len);
+            }
+        }
+        Some(len)
+    }
+
+    /// The leftmost run of at least `len` blocks that starts in `within`.
+    fn leftmost(&mut self, len: usize, within: Range<usize>) -> Option<usize> {
+        let mut best = None;
+        for starts in self.starts_by_len.range(len..).map(|(_, starts)| starts) {
+            #[cfg(test)]
+            {
+                self.lookups += 1;
+            }
+            // Only a run left of the best so far will do
+            let end = best.unwrap_or(within.end);
+            if let Some(&start) = starts.range(within.start..end).next() {
+                best = Some(start);
+            }
+        }
+        best
+    }
+
+    /// The leftmost run of the shortest length of at least `len` blocks, or of the longest length
+    /// if `longest` is set, that has a run starting before `before`.
+    fn by_length(&mut self, len: usize, before: usize, longest: bool) -> Option<usize> {
+        let mut lengths = self.starts_by_len.range(len..);
+        loop {
+            let (_, starts) = if longest {
+                lengths.next_back()
+            } else {
+                lengths.next()
+            }?;
+            #[cfg(test)]
+            {
+                self.lookups += 1;
+            }
+            match starts.first() {
+                Some(&start) if start < before => return Some(start),
+                _ => (),
+            }
+        }
+    }
+
+    /// Where the run `fit` picks out of those of at least `len` blocks that start before
+    /// `before` starts. `next` is where [`Fit::Next`] starts looking.
+    ///
+    /// Every fit goes through the lengths of at least `len` that some run has, taking `O(log n)`
+    /// for each: [`Fit::Best`] and [`Fit::Worst`] stop at the first length with a run before
+    /// `before`, and [`Fit::First`] and [`Fit::Next`] go through them all.
+    fn find(&mut self, fit: Fit, len: usize, before: usize, next: usize) -> Option<usize> {
+        match fit {
+            Fit::First => self.leftmost(len, 0..before),
+            Fit::Best => self.by_length(len, before, false),
+            Fit::Worst => self.by_length(len, before, true),
+            Fit::Next => {
+                let next = next.min(before);
+                self.leftmost(len, next..before)
+                    .or_else(|| self.leftmost(len, 0..next))
+            }
+        }
+    }
+
+    /// Takes the first `len` blocks of the run starting at `start`.
+    fn take(&mut self, start: usize, len: usize) {
+        let run_len = self.remove(start).expect("taking from a free run");
+        assert!(run_len >= len, "taking more than a free run holds");
+        self.insert(Span {
+            start: start + len,
+            len: run_len - len,
+        });
+    }
+
+    /// Frees `span`, merging it with the runs on either side.
+    fn release(&mut self, mut span: Span) {
+        if let Some((&start, &len)) = self.runs.range(..span.start).next_back() {
+            if start + len == span.start {
+                self.remove(start);
+                span = Span {
+                    start,
+                    len: len + span.len,
+                };
+            }
+        }
+        if let Some(len) = self.remove(span.end()) {
+            span.len += len;
+        }
+        self.insert(span);
+    }
 }
 
 #[derive(Debug, Clone)]
 struct Disk {
-    cells: Vec<Cell>,
+    /// Ordered by id
+    files: Vec<File>,
+    free: FreeSpace,
+    /// How many blocks the disk has
+    size: usize,
 }
 
 impl FromStr for Disk {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let digits = s
-            .trim()
-            .chars()
-            .map(|c| c.to_string().parse::<i32>())
-            .collect::<Result<Vec<i32>, _>>()?;
+        let mut size = 0;
+        let lens = s
+            .trim_end()
+            .char_indices()
+            .map(|(idx, c)| {
+                let len = c.to_digit(10).map(|n| n as usize).ok_or_else(|| {
+                    ParseError::at_offset(s, idx, format!("expected a digit, found {c:?}"))
+                })?;
+                size = grow(size, len).map_err(|err| ParseError::at_offset(s, idx, err))?;
+                Ok(len)
+            })
+            .collect::<Result<Vec<usize>, _>>()?;
 
-        let cells = digits
+        // The disk map alternates between files, numbered from 0, and free space
+        let spans = lens
             .iter()
             .enumerate()
-            .map(|(idx, n)| {
-                if idx % 2 == 0 {
-                    Cell::File(File {
-                        id: (idx / 2) as i32,
-                        size: *n,
-                    })
-                } else {
-                    Cell::Free(Free { size: *n })
-                }
-            })
-            .collect_vec();
-        Ok(Disk { cells })
+            .map(|(idx, len)| ((idx % 2 == 0).then_some(idx / 2), *len));
+        Ok(Disk::from_spans(spans))
+    }
+}
+
+impl Display for Disk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spans = self.spans().into_iter().map(|(id, len)| match id {
+            Some(id) => format!("{id}:{len}"),
+            None => format!(".:{len}"),
+        });
+        write!(f, "{}", spans.format(" "))
     }
 }
 
 impl Disk {
-    fn last_file(&self) -> Option<usize> {
-        self.cells
+    fn parse(input: &str, format: Format) -> Result<Self, ParseError> {
+        match format {
+            Format::Dense => input.parse(),
+            Format::Spans => Disk::from_span_list(input),
+            Format::Layout => Disk::from_layout(input),
+        }
+    }
+
+    /// Reads the [`Format::Spans`] that [`Disk`] displays as.
+    fn from_span_list(s: &str) -> Result<Self, ParseError> {
+        let mut size = 0;
+        let spans = s
+            .split_ascii_whitespace()
+            .map(|token| {
+                let (id, len) = token
+                    .split_once(':')
+                    .ok_or_else(|| ParseError::at(s, token, "expected ID:LEN or .:LEN"))?;
+                let id = match id {
+                    "." => None,
+                    _ => Some(id.parse().map_err(|_| {
+                        ParseError::at(s, id, format!("expected a file id, found {id:?}"))
+                    })?),
+                };
+                let blocks = len.parse().map_err(|_| {
+                    ParseError::at(s, len, format!("expected a length, found {len:?}"))
+                })?;
+                size = grow(size, blocks).map_err(|err| ParseError::at(s, len, err))?;
+                Ok((id, blocks))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Disk::from_spans(spans))
+    }
+
+    /// Reads the [`Format::Layout`] that [`Disk::render`] draws.
+    fn from_layout(s: &str) -> Result<Self, ParseError> {
+        let mut spans: Vec<(Option<usize>, usize)> = Vec::new();
+        let mut size = 0;
+        for (idx, c) in s.trim_end().char_indices() {
+            size = grow(size, 1).map_err(|err| ParseError::at_offset(s, idx, err))?;
+            let id = match c {
+                '.' => None,
+                _ => Some(c.to_digit(10).ok_or_else(|| {
+                    ParseError::at_offset(s, idx, format!("expected a digit or '.', found {c:?}"))
+                })? as usize),
+            };
+            match spans.last_mut() {
+                Some((last, len)) if *last == id => *len += 1,
+                _ => spans.push((id, 1)),
+            }
+        }
+        Ok(Disk::from_spans(spans))
+    }
+
+    /// The disk's spans in order, as the file each belongs to or `None` for free space, and its
+    /// length. The inverse of [`Disk::from_spans`].
+    fn spans(&self) -> Vec<(Option<usize>, usize)> {
+        let files = self
+            .files
             .iter()
-            .enumerate()
-            .rev()
-            .find_map(|(idx, x)| match x {
-                Cell::File(_) => Some(idx),
-                _ => None,
+            .flat_map(|file| file.spans.iter().map(|span| (Some(file.id), *span)));
+        let free = self
+            .free
+            .runs
+            .iter()
+            .map(|(&start, &len)| (None, Span { start, len }));
+        files
+            .chain(free)
+            .sorted_by_key(|(_, span)| span.start)
+            .map(|(id, span)| (id, span.len))
+            .collect()
+    }
+
+    /// A disk laid out as `spans` of blocks in a row, each belonging to the file with the given
+    /// id or free. The parsers have made sure they add up to at most [`MAX_BLOCKS`].
+    fn from_spans(spans: impl IntoIterator<Item = (Option<usize>, usize)>) -> Self {
+        let mut pieces = Vec::new();
+        let mut gaps: Vec<Span> = Vec::new();
+        let mut size = 0;
+        for (id, len) in spans {
+            let span = Span { start: size, len };
+            size = grow(size, len).expect("a disk of at most MAX_BLOCKS blocks");
+            match id {
+                Some(id) if len > 0 => pieces.push((id, span)),
+                Some(_) => (),
+                None => match gaps.last_mut() {
+                    Some(gap) if gap.end() == span.start => gap.len += len,
+                    _ => gaps.push(span),
+                },
+            }
+        }
+        pieces.sort_by_key(|(id, _)| *id);
+        let files: Vec<File> = pieces
+            .chunk_by(|(a, _), (b, _)| a == b)
+            .map(|pieces| {
+                let mut spans = pieces.iter().map(|(_, span)| *span).collect();
+                merge_spans(&mut spans);
+                File {
+                    id: pieces[0].0,
+                    spans,
+                }
             })
+            .collect();
+        let free = FreeSpace::from_runs(gaps);
+        Disk { files, free, size }
     }
 
-    fn file_by_id(&self, id: i32) -> (usize, File) {
-        self.cells
+    /// Which file every block belongs to, if any.
+    fn blocks(&self) -> Vec<Option<usize>> {
+        let mut blocks = vec![None; self.size];
+        for file in &self.files {
+            for span in &file.spans {
+                blocks[span.start..span.end()].fill(Some(file.id));
+            }
+        }
+        blocks
+    }
+
+    fn render(&self) -> String {
+        render_blocks(&self.blocks())
+    }
+
+    /// Moves blocks one at a time from the end of the disk into the leftmost free block, until
+    /// there are no gaps between the files.
+    fn compact(&mut self) -> Stats {
+        let gaps: Vec<Span> = self
+            .free
+            .runs
+            .iter()
+            .map(|(&start, &len)| Span { start, len })
+            .collect();
+        // Every piece of every file, by where it starts, as the index of the file and the span
+        let mut pieces: Vec<(usize, Span)> = self
+            .files
             .iter()
             .enumerate()
-            .find_map(|(idx, cell)| match cell {
-                Cell::File(file) if file.id == id => Some((
-                    idx,
-                    File {
-                        id: file.id,
-                        size: file.size,
-                    },
-                )),
-                _ => None,
-            })
-            .unwrap()
+            .flat_map(|(idx, file)| file.spans.iter().map(move |span| (idx, *span)))
+            .sorted_by_key(|(_, span)| span.start)
+            .collect();
+        let mut moved = Vec::new();
+        let mut stats = Stats::default();
+
+        let mut gaps = gaps.into_iter().peekable();
+        while let (Some(gap), Some((file, piece))) = (gaps.peek_mut(), pieces.last_mut()) {
+            if gap.start >= piece.start {
+                break;
+            }
+            // Move as much of the end of the piece as fits
+            let len = gap.len.min(piece.len);
+            moved.push((
+                *file,
+                Span {
+                    start: gap.start,
+                    len,
+                },
+            ));
+            gap.start += len;
+            gap.len -= len;
+            piece.len -= len;
+            stats.moves += 1;
+            stats.blocks_moved += len;
+            if gap.len == 0 {
+                gaps.next();
+            }
+            if piece.len == 0 {
+                pieces.pop();
+            }
+            if visualize::enabled() {
+                let spans = pieces.iter().chain(&moved);
+                visualize::frame(|| self.render_pieces(spans));
+            }
+        }
+
+        for file in &mut self.files {
+            file.spans.clear();
+        }
+        for (file, span) in pieces.into_iter().chain(moved) {
+            self.files[file].spans.push(span);
+        }
+        let mut occupied: Vec<Span> = Vec::new();
+        for file in &mut self.files {
+            merge_spans(&mut file.spans);
+            occupied.extend(&file.spans);
+        }
+        // Whatever isn't taken by a file is free
+        occupied.sort_by_key(|span| span.start);
+        let mut gaps = Vec::new();
+        let mut start = 0;
+        for span in occupied.into_iter().chain([Span {
+            start: self.size,
+            len: 0,
+        }]) {
+            gaps.push(Span {
+                start,
+                len: span.start - start,
+            });
+            start = span.end();
+        }
+        self.free = FreeSpace::from_runs(gaps);
+        Stats {
+            fragmentation: self.fragmentation(),
+            ..stats
+        }
     }
 
-    fn first_free(&self, min_size: i32) -> Option<usize> {
-        self.cells.iter().position(|cell| match cell {
-            Cell::Free(free) => free.size >= min_size,
-            _ => false,
-        })
+    /// Draws a disk that's part way through [`Self::compact`], with its files in `pieces`.
+    fn render_pieces<'a>(&self, pieces: impl Iterator<Item = &'a (usize, Span)>) -> String {
+        let mut blocks = vec![None; self.size];
+        for (file, span) in pieces {
+            blocks[span.start..span.end()].fill(Some(self.files[*file].id));
+        }
+        render_blocks(&blocks)
     }
 
-    fn digits(&self) -> impl Iterator<Item = Option<i32>> + '_ {
-        self.cells.iter().flat_map(|cell| match cell {
-            Cell::File(File { id, size }) => std::iter::repeat(Some(*id)).take(*size as _),
-            Cell::Free(Free { size }) => std::iter::repeat(None).take(*size as _),
-        })
+    /// Moves every file once, highest id first, into the free space before it that `fit` picks
+    /// out of those it fits in whole.
+    fn compact_defragged(&mut self, fit: Fit) -> Stats {
+        let mut stats = Stats::default();
+        let mut next = 0;
+        for idx in (0..self.files.len()).rev() {
+            visualize::frame(|| format!("{}\n{stats}", self.render()));
+            let file = &self.files[idx];
+            let len = file.len();
+            let Some(start) = self.free.find(fit, len, file.start(), next) else {
+                continue;
+            };
+            self.free.take(start, len);
+            let spans = &mut self.files[idx].spans;
+            for span in spans.drain(..) {
+                self.free.release(span);
+            }
+            spans.push(Span { start, len });
+            next = start + len;
+            stats.moves += 1;
+            stats.blocks_moved += len;
+        }
+        Stats {
+            fragmentation: self.fragmentation(),
+            ..stats
+        }
     }
 
-    fn show(&self) {
-        let s = self
-            .digits()
-            .map(|x| match x {
-                Some(n) => n.to_string(),
-                _ => ".".to_string(),
+    /// Packs all the files together in one piece each at the start of the disk, keeping them in
+    /// the order they start in.
+    fn defragment(&mut self) -> Stats {
+        let mut stats = Stats::default();
+        let mut start = 0;
+        let order: Vec<usize> = (0..self.files.len())
+            .sorted_by_key(|idx| self.files[*idx].start())
+            .collect();
+        for idx in order {
+            visualize::frame(|| format!("{}\n{stats}", self.render()));
+            let file = &mut self.files[idx];
+            let span = Span {
+                start,
+                len: file.len(),
+            };
+            if file.spans != [span] {
+                file.spans = vec![span];
+                stats.moves += 1;
+                stats.blocks_moved += span.len;
+            }
+            start = span.end();
+        }
+        let free = Span {
+            start,
+            len: self.size - start,
+        };
+        self.free = FreeSpace::from_runs(vec![free]);
+        Stats {
+            fragmentation: self.fragmentation(),
+            ..stats
+        }
+    }
+
+    fn run(&mut self, compaction: Compaction) -> Stats {
+        match compaction {
+            Compaction::Blocks => self.compact(),
+            Compaction::Files(fit) => self.compact_defragged(fit),
+            Compaction::Defragment => self.defragment(),
+        }
+    }
+
+    /// How far the disk is from being fully defragmented: the number of pieces its files are in
+    /// beyond one each, plus the number of free spaces between files.
+    fn fragmentation(&self) -> usize {
+        let pieces: usize = self.files.iter().map(|file| file.spans.len() - 1).sum();
+        let end = self
+            .files
+            .iter()
+            .flat_map(|file| file.spans.last())
+            .map(Span::end)
+            .max()
+            .unwrap_or(0);
+        pieces + self.free.runs.range(..end).count()
+    }
+
+    fn checksum(&self) -> u128 {
+        self.files
+            .iter()
+            .flat_map(|file| file.spans.iter().map(|span| (file.id, span)))
+            .map(|(id, span)| {
+                // The sum of the positions start..end
+                let (start, len) = (span.start as u128, span.len as u128);
+                let positions = len * start + len * len.saturating_sub(1) / 2;
+                id as u128 * positions
             })
-            .collect::<String>();
-        println!("{s}");
+            .sum()
     }
+}
+
+fn render_blocks(blocks: &[Option<usize>]) -> String {
+    blocks
+        .iter()
+        .map(|x| match x {
+            Some(n) => n.to_string(),
+            _ => ".".to_string(),
+        })
+        .collect()
+}
+
+/// Sorts `spans` and joins the ones that touch.
+fn merge_spans(spans: &mut Vec<Span>) {
+    spans.sort_by_key(|span| span.start);
+    spans.dedup_by(|span, prev| {
+        let touching = prev.end() == span.start;
+        if touching {
+            prev.len += span.len;
+        }
+        touching
+    });
+}
+
+fn parse(input: &str) -> anyhow::Result<Disk> {
+    Ok(Disk::parse(input, MAP_FORMAT.value()?)?)
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<u128> {
+    let mut disk = parse(input)?;
+    disk.run(PART_1_COMPACTION.value()?);
+    Ok(disk.checksum())
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<u128> {
+    let mut disk = parse(input)?;
+    disk.run(PART_2_COMPACTION.value()?);
+    Ok(disk.checksum())
+}
+
+pub const DAY: aoc_common::Day = aoc_common::day!(
+    2024,
+    9,
+    parse: parse,
+    params: [MAP_FORMAT, PART_1_COMPACTION, PART_2_COMPACTION]
+);
+
+aoc_common::examples! {
+    "example.txt" => { part_1: 1928, part_2: 2858 },
+}
 
-    fn compact(&mut self) {
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::cmp::Reverse;
+
+    /// Part 1 the straightforward way, block by block.
+    fn compact_blocks(mut blocks: Vec<Option<usize>>) -> Vec<Option<usize>> {
+        let (mut free, mut file) = (0, blocks.len());
         loop {
-            // self.show();
-            let file_idx = self.last_file().unwrap();
-            let free_idx = self.first_free(0).unwrap();
-            if free_idx > file_idx {
-                break;
+            while free < blocks.len() && blocks[free].is_some() {
+                free += 1;
+            }
+            while file > 0 && blocks[file - 1].is_none() {
+                file -= 1;
+            }
+            if file == 0 || free >= file - 1 {
+                return blocks;
             }
+            blocks.swap(free, file - 1);
+        }
+    }
 
-            self.move_file(file_idx, free_idx).unwrap();
+    /// Part 2 the straightforward way, block by block.
+    fn compact_files(mut blocks: Vec<Option<usize>>, fit: Fit) -> Vec<Option<usize>> {
+        let ids: Vec<usize> = blocks.iter().flatten().copied().unique().collect();
+        let mut next = 0;
+        for id in ids.into_iter().sorted().rev() {
+            let start = blocks.iter().position(|b| *b == Some(id)).unwrap();
+            let len = blocks.iter().filter(|b| **b == Some(id)).count();
+            // The free spaces before the file that it fits in, as where they start and their length
+            let gaps: Vec<(usize, usize)> = blocks[..start]
+                .iter()
+                .enumerate()
+                .chunk_by(|(_, b)| b.is_none())
+                .into_iter()
+                .filter(|(free, _)| *free)
+                .map(|(_, run)| {
+                    let run: Vec<_> = run.collect();
+                    (run[0].0, run.len())
+                })
+                .filter(|(_, gap_len)| *gap_len >= len)
+                .collect();
+            let gap = match fit {
+                Fit::First => gaps.first().copied(),
+                Fit::Best => gaps.iter().copied().min_by_key(|(_, gap_len)| *gap_len),
+                Fit::Worst => gaps
+                    .iter()
+                    .copied()
+                    .max_by_key(|(s, gap_len)| (*gap_len, Reverse(*s))),
+                Fit::Next => gaps
+                    .iter()
+                    .find(|(s, _)| *s >= next)
+                    .or(gaps.first())
+                    .copied(),
+            };
+            if let Some((gap, _)) = gap {
+                blocks
+                    .iter_mut()
+                    .filter(|b| **b == Some(id))
+                    .for_each(|b| *b = None);
+                blocks[gap..gap + len].fill(Some(id));
+                next = gap + len;
+            }
         }
+        blocks
     }
 
-    fn move_file(&mut self, file_idx: usize, free_idx: usize) -> anyhow::Result<()> {
-        let file = match self.cells.get(file_idx) {
-            Some(Cell::File(file)) => Ok(*file),
-            _ => Err(anyhow!("bad file_idx")),
-        }?;
-        let free = match self.cells.get(free_idx) {
-            Some(Cell::Free(free)) => Ok(*free),
-            _ => Err(anyhow!("bad free_idx")),
-        }?;
+    fn disk_map() -> impl Strategy<Value = String> {
+        prop::collection::vec(0..10u32, 1..40).prop_map(|lens| {
+            lens.iter()
+                .map(|n| char::from_digit(*n, 10).unwrap())
+                .collect()
+        })
+    }
 
-        if free_idx > file_idx {
-            return Err(anyhow!("free_idx > file_idx"));
+    /// Spans of files with ids below `ids`, in any order and possibly in pieces, and free space.
+    fn spans(ids: usize) -> impl Strategy<Value = Vec<(Option<usize>, usize)>> {
+        prop::collection::vec((prop::option::of(0..ids), 1..12usize), 0..20)
+    }
+
+    proptest! {
+        #[test]
+        fn test_span_list_round_trip(spans in spans(1000)) {
+            let disk = Disk::from_spans(spans);
+            let read = Disk::from_span_list(&disk.to_string()).unwrap();
+            prop_assert_eq!(read.spans(), disk.spans());
+            prop_assert_eq!(read.blocks(), disk.blocks());
         }
 
-        if file.size == free.size {
-            self.cells[free_idx] = Cell::File(file);
-            self.cells[file_idx] = Cell::Free(free);
-        } else if file.size < free.size {
-            self.cells[free_idx] = Cell::Free(Free {
-                size: free.size - file.size,
-            });
-            self.cells[file_idx] = Cell::Free(Free { size: file.size });
-            self.cells.insert(free_idx, Cell::File(file));
-        } else if file.size > free.size {
-            self.cells[free_idx] = Cell::File(File {
-                id: file.id,
-                size: free.size,
-            });
-            self.cells[file_idx] = Cell::File(File {
-                id: file.id,
-                size: file.size - free.size,
-            });
+        #[test]
+        fn test_layout_round_trip(spans in spans(10)) {
+            let disk = Disk::from_spans(spans);
+            let read = Disk::from_layout(&disk.render()).unwrap();
+            prop_assert_eq!(read.spans(), disk.spans());
+        }
+
+        #[test]
+        fn test_compact_layouts_match_blocks(spans in spans(10)) {
+            let disk = Disk::from_spans(spans);
+            let mut compacted = disk.clone();
+            compacted.compact();
+            prop_assert_eq!(compacted.blocks(), compact_blocks(disk.blocks()));
+            let mut defragged = disk.clone();
+            defragged.compact_defragged(Fit::First);
+            prop_assert_eq!(defragged.blocks(), compact_files(disk.blocks(), Fit::First));
         }
 
-        // merge free cells if needed
-        match (self.cells[file_idx - 1], self.cells[file_idx]) {
-            (Cell::Free(cell_a), Cell::Free(cell_b)) => {
-                let size = cell_a.size + cell_b.size;
-                self.cells[file_idx - 1] = Cell::Free(Free { size });
-                self.cells.remove(file_idx);
+        #[test]
+        fn test_fits_match_blocks(spans in spans(10)) {
+            let disk = Disk::from_spans(spans);
+            for fit in [Fit::First, Fit::Best, Fit::Worst, Fit::Next] {
+                let mut compacted = disk.clone();
+                let stats = compacted.compact_defragged(fit);
+                prop_assert_eq!(compacted.blocks(), compact_files(disk.blocks(), fit), "{:?}", fit);
+                prop_assert_eq!(stats.fragmentation, compacted.fragmentation());
             }
-            _ => (),
-        };
+        }
+
+        #[test]
+        fn test_defragment(spans in spans(10)) {
+            let mut disk = Disk::from_spans(spans);
+            let used = disk.files.iter().map(File::len).sum::<usize>();
+            let order: Vec<usize> = disk.files.iter().sorted_by_key(|f| f.start()).map(|f| f.id).collect();
+            let stats = disk.defragment();
+            prop_assert_eq!(stats.fragmentation, 0);
+            prop_assert!(stats.blocks_moved <= used);
+            let blocks = disk.blocks();
+            prop_assert!(blocks[..used].iter().all(Option::is_some));
+            let packed: Vec<usize> = blocks[..used].iter().flatten().copied().dedup().collect();
+            prop_assert_eq!(packed, order);
+        }
 
-        Ok(())
+        #[test]
+        fn test_compact_matches_blocks(map in disk_map()) {
+            let mut disk = map.parse::<Disk>().unwrap();
+            let expected = compact_blocks(disk.blocks());
+            disk.compact();
+            prop_assert_eq!(disk.blocks(), expected);
+        }
+
+        #[test]
+        fn test_compact_defragged_matches_blocks(map in disk_map()) {
+            let mut disk = map.parse::<Disk>().unwrap();
+            let expected = compact_files(disk.blocks(), Fit::First);
+            disk.compact_defragged(Fit::First);
+            prop_assert_eq!(disk.blocks(), expected);
+        }
     }
 
-    fn compact_defragged(&mut self) {
-        let max_file_id = self.digits().filter_map(|x| x).max().unwrap();
-        for id in (0..=max_file_id).rev() {
-            // self.show();
-            let (file_idx, file) = self.file_by_id(id);
-            let free_idx = match self.first_free(file.size) {
-                Some(o) => o,
-                _ => continue,
-            };
-            if free_idx > file_idx {
-                continue;
+    #[test]
+    fn test_render() {
+        let mut disk = "2333133121414131402".parse::<Disk>().unwrap();
+        assert_eq!(disk.render(), "00...111...2...333.44.5555.6666.777.888899");
+        disk.compact_defragged(Fit::First);
+        assert_eq!(disk.render(), "00992111777.44.333....5555.6666.....8888..");
+    }
+
+    #[test]
+    fn test_hand_crafted_layouts() {
+        // The layout, then after compacting and after compacting whole files
+        let layouts = [
+            ("00...111...2", "002111......", "002..111...."),
+            ("0..11.2", "0211...", "02.11.."),
+            ("2.1..0", "201...", "210..."),
+            ("000.1111....22", "000211112.....", "000.111122...."),
+            ("....0", "0....", "0...."),
+            ("0.1.0.1", "0110...", "0.1.0.1"),
+        ];
+        for (layout, compacted, defragged) in layouts {
+            let disk = Disk::parse(layout, Format::Layout).unwrap();
+            assert_eq!(disk.render(), layout);
+            let mut disk_1 = disk.clone();
+            disk_1.compact();
+            assert_eq!(disk_1.render(), compacted, "{layout}");
+            let mut disk_2 = disk.clone();
+            disk_2.compact_defragged(Fit::First);
+            assert_eq!(disk_2.render(), defragged, "{layout}");
+        }
+    }
+
+    #[test]
+    fn test_fits() {
+        // The layout, then after moving the files to the first, best, worst and next fit
+        let layouts = [
+            (
+                "0....1..22",
+                ["0221......", "01....22..", "0221......", "0221......"],
+            ),
+            (
+                "0..1....22",
+                ["0221......", "0221......", "01..22....", "0221......"],
+            ),
+            (
+                "0.1...2...33",
+                [
+                    "02133.......",
+                    "02133.......",
+                    "02133.......",
+                    "01.332......",
+                ],
+            ),
+        ];
+        let fits = [Fit::First, Fit::Best, Fit::Worst, Fit::Next];
+        for (layout, expected) in layouts {
+            let disk = Disk::parse(layout, Format::Layout).unwrap();
+            for (fit, expected) in fits.into_iter().zip(expected) {
+                let mut compacted = disk.clone();
+                compacted.compact_defragged(fit);
+                assert_eq!(compacted.render(), expected, "{layout} {fit:?}");
             }
-            self.move_file(file_idx, free_idx).unwrap()
         }
     }
 
-    fn checksum(&self) -> i64 {
-        self.digits()
-            .enumerate()
-            .filter_map(|(idx, n)| match n {
-                Some(n) => Some(idx as i64 * n as i64),
-                None => None,
-            })
-            .sum()
+    #[test]
+    fn test_stats() {
+        let disk = "2333133121414131402".parse::<Disk>().unwrap();
+        assert_eq!(disk.fragmentation(), 8);
+        let stats = |compaction| disk.clone().run(compaction);
+        // Files 8 and 6 end up in two and three pieces
+        assert_eq!(
+            stats(Compaction::Blocks),
+            Stats {
+                moves: 7,
+                blocks_moved: 12,
+                fragmentation: 3
+            }
+        );
+        assert_eq!(
+            stats(Compaction::Files(Fit::First)),
+            Stats {
+                moves: 4,
+                blocks_moved: 8,
+                fragmentation: 5
+            }
+        );
+        let stats = stats(Compaction::Defragment);
+        assert_eq!(
+            stats.to_string(),
+            "9 moves, 26 blocks moved, fragmentation 0"
+        );
+    }
+
+    #[test]
+    fn test_formats() {
+        let dense = "2333133121414131402".parse::<Disk>().unwrap();
+        let spans = Disk::parse(&dense.to_string(), Format::Spans).unwrap();
+        assert_eq!(spans.blocks(), dense.blocks());
+        assert!(dense
+            .to_string()
+            .starts_with("0:2 .:3 1:3 .:3 2:1 .:3 3:3 .:1 4:2"));
+
+        // Spans may be longer than 9 blocks, and files needn't be numbered in order
+        let disk = Disk::parse("12:10 .:12\n3:1\n", Format::Spans).unwrap();
+        assert_eq!(disk.to_string(), "12:10 .:12 3:1");
+        assert_eq!(disk.size, 23);
+        let mut defragged = disk.clone();
+        defragged.compact_defragged(Fit::First);
+        assert_eq!(defragged.to_string(), "12:10 3:1 .:12");
+        assert_eq!(defragged.checksum(), 12 * 45 + 3 * 10);
+
+        assert_eq!(
+            "sparse".parse::<Format>(),
+            Err("expected dense, spans or layout".to_owned())
+        );
+        assert_eq!("best-fit".parse(), Ok(Compaction::Files(Fit::Best)));
+        assert!("best".parse::<Compaction>().is_err());
     }
-}
 
-pub fn main() -> anyhow::Result<()> {
-    let disk = {
-        let mut s = String::new();
-        stdin().read_to_string(&mut s)?;
-        s.parse::<Disk>()?
-    };
+    #[test]
+    fn test_parse_errors() {
+        let errors = [
+            ("23a1", Format::Dense, 3, "expected a digit, found 'a'"),
+            ("0:2 .:3 1-3", Format::Spans, 9, "expected ID:LEN or .:LEN"),
+            (
+                "0:2 x:3",
+                Format::Spans,
+                5,
+                "expected a file id, found \"x\"",
+            ),
+            (
+                "0:2 .:-3",
+                Format::Spans,
+                7,
+                "expected a length, found \"-3\"",
+            ),
+            (
+                "00..x1",
+                Format::Layout,
+                5,
+                "expected a digit or '.', found 'x'",
+            ),
+            // Too big to lay out block by block, or to even count the blocks of
+            (
+                "0:18446744073709551615 .:1",
+                Format::Spans,
+                3,
+                "a span can't be more than 16777216 blocks",
+            ),
+            (
+                "0:2000000000 .:1",
+                Format::Spans,
+                3,
+                "a span can't be more than 16777216 blocks",
+            ),
+            (
+                "0:16777210 .:6 1:1",
+                Format::Spans,
+                18,
+                "a disk can't be more than 16777216 blocks",
+            ),
+        ];
+        for (input, format, column, message) in errors {
+            let err = Disk::parse(input, format).unwrap_err();
+            assert_eq!(
+                (err.column, err.message.as_str()),
+                (column, message),
+                "{input}"
+            );
+        }
+    }
 
-    {
-        let mut disk = disk.clone();
-        disk.compact();
-        println!("part 1: {}", disk.checksum());
+    /// A disk map of `len` digits that look random.
+    fn large_map(len: u32) -> String {
+        (0..len)
+            .map(|n| char::from_digit(n.wrapping_mul(2_654_435_761) % 10, 10).unwrap())
+            .collect()
     }
-    {
-        let mut disk = disk.clone();
-        disk.compact_defragged();
-        println!("part 2: {}", disk.checksum());
+
+    #[test]
+    fn test_lookups_grow_linearly() {
+        // Ten times the disk should take about ten times the lookups, not a hundred
+        let lookups = |len, fit| {
+            let mut disk = large_map(len).parse::<Disk>().unwrap();
+            disk.compact_defragged(fit);
+            disk.free.lookups
+        };
+        for fit in [Fit::First, Fit::Best, Fit::Worst, Fit::Next] {
+            let (small, large) = (lookups(4_000, fit), lookups(40_000, fit));
+            assert!(large < 25 * small, "{fit:?}: {small} then {large} lookups");
+        }
     }
 
-    Ok(())
+    /// Compacts `disk` block by block and a whole file at a time with each of `fits`, checking
+    /// the files end up in one piece each.
+    fn assert_compacts(disk: &Disk, fits: &[Fit]) {
+        let used: usize = disk.files.iter().map(File::len).sum();
+        let mut compacted = disk.clone();
+        compacted.compact();
+        let free: Vec<_> = compacted.free.runs.clone().into_iter().collect();
+        assert_eq!(free, [(used, disk.size - used)]);
+
+        for &fit in fits {
+            let mut defragged = disk.clone();
+            defragged.compact_defragged(fit);
+            let mut spans: Vec<Span> = defragged
+                .files
+                .iter()
+                .flat_map(|f| f.spans.clone())
+                .collect();
+            assert_eq!(spans.len(), disk.files.len());
+            spans.sort_by_key(|span| span.start);
+            assert!(spans.windows(2).all(|w| w[0].end() <= w[1].start));
+        }
+    }
+
+    /// Both parts of the puzzle on ten times the size of its input, which takes far too long if
+    /// compacting ever goes quadratic again.
+    #[test]
+    fn test_large_disk() {
+        assert_compacts(&large_map(200_000).parse::<Disk>().unwrap(), &[Fit::First]);
+    }
+
+    /// A hundred times the size of the puzzle input, run with `cargo test -- --ignored` to time it.
+    #[test]
+    #[ignore = "benchmark"]
+    fn test_huge_disk() {
+        let fits = [Fit::First, Fit::Best, Fit::Worst, Fit::Next];
+        assert_compacts(&large_map(2_000_000).parse::<Disk>().unwrap(), &fits);
+    }
 }
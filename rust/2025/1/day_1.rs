@@ -1,4 +1,18 @@
-use std::io::stdin;
+use anyhow::bail;
+use aoc_common::{Param, ParseError};
+use std::str::FromStr;
+
+const DIAL_START: Param = Param {
+    name: "dial-start",
+    default: "50",
+    help: "The number the dial points at to begin with",
+};
+
+const DIAL_SIZE: Param = Param {
+    name: "dial-size",
+    default: "100",
+    help: "How many numbers are on the dial, counting from 0",
+};
 
 #[derive(Debug, PartialEq)]
 enum Rotation {
@@ -6,25 +20,53 @@ enum Rotation {
     Right(u32),
 }
 
-impl From<&str> for Rotation {
-    fn from(value: &str) -> Self {
-        let n = u32::from_str_radix(&value[1..], 10).unwrap();
-        match value.chars().next() {
-            Some('L') => Rotation::Left(n),
-            Some('R') => Rotation::Right(n),
-            _ => panic!("invalid data"),
+impl FromStr for Rotation {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rotation, n): (fn(u32) -> Rotation, _) = match s.split_at_checked(1) {
+            Some(("L", n)) => (Rotation::Left, n),
+            Some(("R", n)) => (Rotation::Right, n),
+            _ => return Err("expected a rotation like `L68` or `R14`"),
+        };
+        n.parse()
+            .map(rotation)
+            .map_err(|_| "expected a number of clicks")
+    }
+}
+
+struct Dial {
+    start: i64,
+    size: i64,
+}
+
+impl Dial {
+    fn from_params() -> anyhow::Result<Self> {
+        Self::new(DIAL_START.value()?, DIAL_SIZE.value()?)
+    }
+
+    fn new(start: i64, size: i64) -> anyhow::Result<Self> {
+        if size < 1 {
+            bail!("the dial needs at least one number");
+        }
+        if !(0..size).contains(&start) {
+            bail!(
+                "the dial has to start at one of its numbers, 0 to {}",
+                size - 1
+            );
         }
+        Ok(Self { start, size })
     }
 }
 
-fn part1(input: &[Rotation]) -> u32 {
-    let mut acc = 50;
-    let values: Vec<u32> = input
+fn count_stops_at_zero(dial: &Dial, input: &[Rotation]) -> u32 {
+    let mut acc = dial.start;
+    let values: Vec<i64> = input
         .iter()
         .map(|rot| {
             acc = match rot {
-                Rotation::Left(n) => (10000 + acc - n) % 100,
-                Rotation::Right(n) => (acc + n) % 100,
+                Rotation::Left(n) => (acc - i64::from(*n)).rem_euclid(dial.size),
+                Rotation::Right(n) => (acc + i64::from(*n)).rem_euclid(dial.size),
             };
             acc
         })
@@ -34,8 +76,8 @@ fn part1(input: &[Rotation]) -> u32 {
     zeroes as u32
 }
 
-fn part2(input: &[Rotation]) -> u32 {
-    let mut acc: i32 = 50;
+fn count_clicks_at_zero(dial: &Dial, input: &[Rotation]) -> u32 {
+    let mut acc = dial.start;
     let mut zeroes: u32 = 0;
     for rot in input {
         let (step, n) = match rot {
@@ -44,11 +86,11 @@ fn part2(input: &[Rotation]) -> u32 {
         };
         for _ in 0..*n {
             acc += step;
-            if acc > 99 {
+            if acc >= dial.size {
                 acc = 0;
             }
             if acc < 0 {
-                acc = 99;
+                acc = dial.size - 1;
             }
             if acc == 0 {
                 zeroes += 1;
@@ -59,22 +101,75 @@ fn part2(input: &[Rotation]) -> u32 {
     zeroes
 }
 
-fn main() {
-    let input: Vec<Rotation> = stdin()
-        .lines()
-        .map(|s| Rotation::from(s.unwrap().as_ref()))
-        .collect();
+fn parse(input: &str) -> Result<Vec<Rotation>, ParseError> {
+    aoc_common::parse_lines(input, str::parse)
+}
+
+pub fn part_1(input: &str) -> anyhow::Result<u32> {
+    let rotations = parse(input)?;
+    Ok(count_stops_at_zero(&Dial::from_params()?, &rotations))
+}
+
+pub fn part_2(input: &str) -> anyhow::Result<u32> {
+    let rotations = parse(input)?;
+    Ok(count_clicks_at_zero(&Dial::from_params()?, &rotations))
+}
+
+pub const DAY: aoc_common::Day =
+    aoc_common::day!(2025, 1, parse: parse, params: [DIAL_START, DIAL_SIZE]);
 
-    println!("part 1: {}", part1(&input));
-    println!("part 2: {}", part2(&input));
+aoc_common::examples! {
+    "example.txt" => { part_1: 3, part_2: 6 },
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
+
+    fn rotation() -> impl Strategy<Value = Rotation> {
+        prop_oneof![
+            (0..1000u32).prop_map(Rotation::Left),
+            (0..1000u32).prop_map(Rotation::Right),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_round_trip(rotations in prop::collection::vec(rotation(), 0..20)) {
+            let input: String = rotations
+                .iter()
+                .map(|r| match r {
+                    Rotation::Left(n) => format!("L{n}\n"),
+                    Rotation::Right(n) => format!("R{n}\n"),
+                })
+                .collect();
+            prop_assert_eq!(parse(&input).unwrap(), rotations);
+        }
+    }
 
     #[test]
     fn test_parser() {
-        assert_eq!(Rotation::from("L68"), Rotation::Left(68));
+        assert_eq!("L68".parse(), Ok(Rotation::Left(68)));
+        assert_eq!("R5".parse(), Ok(Rotation::Right(5)));
+    }
+
+    #[test]
+    fn test_parser_errors() {
+        let err = parse("L68\nR5\nX3\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 1, "X3"));
+        assert!("L".parse::<Rotation>().is_err());
+        assert!("Ltwo".parse::<Rotation>().is_err());
+    }
+
+    #[test]
+    fn test_dial_errors() {
+        let err = Dial::new(0, 0).err().unwrap();
+        assert_eq!(err.to_string(), "the dial needs at least one number");
+        let err = Dial::new(100, 100).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "the dial has to start at one of its numbers, 0 to 99"
+        );
     }
 }
@@ -0,0 +1,70 @@
+//! A stand-in for adventofcode.com, for testing the real HTTP client end to end.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread,
+};
+
+/// A request as the server got it.
+#[derive(Debug)]
+pub struct Request {
+    /// The request line and headers
+    pub head: String,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.head.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+}
+
+pub struct FakeServer {
+    pub url: String,
+    handle: thread::JoinHandle<Vec<Request>>,
+}
+
+impl FakeServer {
+    /// Answers one request with each of `responses`, a status line like `200 OK` and a body, in
+    /// order, and then stops.
+    pub fn start(responses: Vec<(&'static str, String)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = Request {
+                    head: String::new(),
+                    body: String::new(),
+                };
+                while reader.read_line(&mut request.head).unwrap() > 2 {}
+                let length = request
+                    .header("content-length")
+                    .map_or(0, |n| n.parse().unwrap());
+                let mut bytes = vec![0; length];
+                reader.read_exact(&mut bytes).unwrap();
+                request.body = String::from_utf8(bytes).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+        Self { url, handle }
+    }
+
+    /// Waits for the server to have answered all its requests, and returns them.
+    pub fn requests(self) -> Vec<Request> {
+        self.handle.join().unwrap()
+    }
+}
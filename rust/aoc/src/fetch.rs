@@ -0,0 +1,276 @@
+use anyhow::{anyhow, bail, Context};
+use aoc_common::Day;
+use std::{
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// The environment variable holding the value of the site's `session` cookie.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// The HTTP layer behind [`Fetcher`] and [`crate::Submitter`], so tests can talk to a stub
+/// instead of the real site.
+pub trait Client {
+    /// GETs `url` with `session` as the session cookie and returns the response body.
+    fn get(&self, url: &str, session: &str) -> anyhow::Result<String>;
+
+    /// POSTs `form` to `url`, URL encoded, with `session` as the session cookie and returns the
+    /// response body.
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> anyhow::Result<String>;
+}
+
+/// The session token in [`SESSION_VAR`].
+pub(crate) fn session_from_env() -> anyhow::Result<String> {
+    let session = std::env::var(SESSION_VAR)
+        .with_context(|| format!("{SESSION_VAR} must hold your adventofcode.com session"))?;
+    Ok(session.trim().to_owned())
+}
+
+pub struct HttpClient {
+    agent: ureq::Agent,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent("github.com/ahem/advent-of-code (aoc)")
+            .timeout(Duration::from_secs(30))
+            .build();
+        Self { agent }
+    }
+}
+
+impl HttpClient {
+    fn body(url: &str, response: Result<ureq::Response, ureq::Error>) -> anyhow::Result<String> {
+        match response {
+            Ok(response) => Ok(response.into_string()?),
+            Err(ureq::Error::Status(code, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                Err(anyhow!("{url}: HTTP {code}: {}", body.trim()))
+            }
+            Err(err) => Err(err).with_context(|| format!("requesting {url}")),
+        }
+    }
+}
+
+impl Client for HttpClient {
+    fn get(&self, url: &str, session: &str) -> anyhow::Result<String> {
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={session}"))
+            .call();
+        Self::body(url, response)
+    }
+
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> anyhow::Result<String> {
+        let response = self
+            .agent
+            .post(url)
+            .set("Cookie", &format!("session={session}"))
+            .send_form(form);
+        Self::body(url, response)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already on disk, so nothing was requested
+    Cached,
+    Downloaded,
+}
+
+/// Downloads puzzle inputs into the cache next to each day's source, leaving the site alone for
+/// inputs that are already there and spacing out the requests it does make.
+pub struct Fetcher<C> {
+    client: C,
+    base_url: String,
+    session: String,
+    /// The minimum time between two requests
+    interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Fetcher<HttpClient> {
+    /// A fetcher for the real site, using the session token in [`SESSION_VAR`].
+    pub fn from_env() -> anyhow::Result<Self> {
+        Ok(Self::new(
+            HttpClient::default(),
+            BASE_URL,
+            &session_from_env()?,
+        ))
+    }
+}
+
+impl<C: Client> Fetcher<C> {
+    pub fn new(client: C, base_url: &str, session: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+            interval: Duration::from_secs(3),
+            last_request: None,
+        }
+    }
+
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    pub fn input_url(&self, year: u32, day: u32) -> String {
+        format!("{}/{year}/day/{day}/input", self.base_url)
+    }
+
+    /// Makes sure the input for `day` is in its `input.txt`, downloading it if it isn't.
+    pub fn fetch(&mut self, day: &Day) -> anyhow::Result<Fetched> {
+        self.fetch_to(day.year, day.day, &day.input_path())
+    }
+
+    /// Makes sure the input for `year` and `day` is at `path`, downloading it if it isn't.
+    pub fn fetch_to(&mut self, year: u32, day: u32, path: &Path) -> anyhow::Result<Fetched> {
+        if path.exists() {
+            return Ok(Fetched::Cached);
+        }
+
+        if let Some(elapsed) = self.last_request.map(|t| t.elapsed()) {
+            thread::sleep(self.interval.saturating_sub(elapsed));
+        }
+        self.last_request = Some(Instant::now());
+        let input = self.client.get(&self.input_url(year, day), &self.session)?;
+        if input.trim().is_empty() {
+            bail!("got an empty input for {year} day {day}");
+        }
+
+        // Write next to the cache and move into place, so a failed write never leaves a
+        // truncated input behind to be mistaken for a cached one
+        let partial = path.with_extension("txt.partial");
+        fs::write(&partial, input).with_context(|| format!("writing {}", partial.display()))?;
+        fs::rename(&partial, path).with_context(|| format!("writing {}", path.display()))?;
+        Ok(Fetched::Downloaded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_server::FakeServer;
+    use std::{cell::RefCell, path::PathBuf};
+
+    /// Records the requests it gets and answers each with `body`.
+    struct FakeClient {
+        body: &'static str,
+        requests: RefCell<Vec<(String, String)>>,
+    }
+
+    impl Client for &FakeClient {
+        fn get(&self, url: &str, session: &str) -> anyhow::Result<String> {
+            self.requests
+                .borrow_mut()
+                .push((url.to_owned(), session.to_owned()));
+            Ok(self.body.to_owned())
+        }
+
+        fn post(&self, _: &str, _: &str, _: &[(&str, &str)]) -> anyhow::Result<String> {
+            unreachable!("fetching only GETs")
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_fetch_caches() {
+        let dir = temp_dir("cache");
+        let path = dir.join("input.txt");
+        let client = FakeClient {
+            body: "1\n2\n3\n",
+            requests: RefCell::new(vec![]),
+        };
+        let mut fetcher = Fetcher::new(&client, "http://example.com/", "secret");
+
+        assert_eq!(
+            fetcher.fetch_to(2024, 1, &path).unwrap(),
+            Fetched::Downloaded
+        );
+        assert_eq!(fetcher.fetch_to(2024, 1, &path).unwrap(), Fetched::Cached);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        assert_eq!(
+            *client.requests.borrow(),
+            vec![(
+                "http://example.com/2024/day/1/input".to_owned(),
+                "secret".to_owned()
+            )]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_rate_limits() {
+        let dir = temp_dir("rate-limit");
+        let client = FakeClient {
+            body: "input",
+            requests: RefCell::new(vec![]),
+        };
+        let interval = Duration::from_millis(100);
+        let mut fetcher = Fetcher::new(&client, "http://example.com", "").with_interval(interval);
+
+        let start = Instant::now();
+        fetcher.fetch_to(2024, 1, &dir.join("1.txt")).unwrap();
+        fetcher.fetch_to(2024, 2, &dir.join("2.txt")).unwrap();
+        assert!(start.elapsed() >= interval);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_http_client() {
+        let server = FakeServer::start(vec![
+            ("200 OK", "12345\n".to_owned()),
+            ("200 OK", "<article>ok</article>".to_owned()),
+        ]);
+        let client = HttpClient::default();
+        let url = &server.url;
+        let body = client
+            .get(&format!("{url}/2022/day/6/input"), "abc")
+            .unwrap();
+        assert_eq!(body, "12345\n");
+        let form = [("level", "1"), ("answer", "a b&c")];
+        let body = client
+            .post(&format!("{url}/2022/day/6/answer"), "abc", &form)
+            .unwrap();
+        assert_eq!(body, "<article>ok</article>");
+
+        let requests = server.requests();
+        assert!(requests[0]
+            .head
+            .starts_with("GET /2022/day/6/input HTTP/1.1\r\n"));
+        assert_eq!(requests[0].header("cookie"), Some("session=abc"));
+        assert!(requests[1]
+            .head
+            .starts_with("POST /2022/day/6/answer HTTP/1.1\r\n"));
+        assert_eq!(requests[1].header("cookie"), Some("session=abc"));
+        assert_eq!(requests[1].body, "level=1&answer=a+b%26c");
+    }
+
+    #[test]
+    fn test_http_client_error_status() {
+        let body = "Please don't repeatedly request this";
+        let server = FakeServer::start(vec![("404 Not Found", body.to_owned())]);
+        let dir = temp_dir("error-status");
+        let path = dir.join("input.txt");
+        let mut fetcher = Fetcher::new(HttpClient::default(), &server.url, "abc");
+
+        let err = fetcher.fetch_to(2030, 1, &path).unwrap_err();
+        server.requests();
+        assert!(err.to_string().contains("HTTP 404"));
+        assert!(!path.exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Context};
+use aoc_common::{BenchOptions, Check, Day, Manifest, Measurement, Outcome};
+
+#[cfg(test)]
+mod fake_server;
+mod fetch;
+mod ocaml;
+mod scaffold;
+mod submit;
+
+pub use fetch::{Client, Fetched, Fetcher, HttpClient, BASE_URL, SESSION_VAR};
+pub use ocaml::{
+    compare, compare_day, ocaml_dir, parse_answers, Agreement, Comparison, Dune, OcamlRunner,
+};
+pub use scaffold::scaffold;
+pub use submit::{parse_response, Submission, Submitter, Verdict};
+
+/// Every solved day, for all years.
+pub fn solutions() -> impl Iterator<Item = &'static Day> {
+    [
+        advent_of_code_2015::DAYS,
+        advent_of_code_2019::DAYS,
+        advent_of_code_2021::DAYS,
+        advent_of_code_2022::DAYS,
+        advent_of_code_2023::DAYS,
+        advent_of_code_2024::DAYS,
+        advent_of_code_2025::DAYS,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// The solved days in `year` (and `day`, if given), or every solved day when `year` is `None`.
+pub fn select(year: Option<u32>, day: Option<u32>) -> impl Iterator<Item = &'static Day> {
+    solutions()
+        .filter(move |d| year.is_none_or(|year| d.year == year))
+        .filter(move |d| day.is_none_or(|day| d.day == day))
+}
+
+pub fn find_day(year: u32, day: u32) -> anyhow::Result<&'static Day> {
+    solutions()
+        .find(|d| d.year == year && d.day == day)
+        .ok_or_else(|| anyhow!("no solution for {year} day {day}"))
+}
+
+/// Runs every solved day in `year` (and `day`, if given) on its `input.txt` and compares the
+/// answers with `manifest`. Days without an input are reported as such instead of being solved.
+pub fn check(manifest: &Manifest, year: Option<u32>, day: Option<u32>) -> Vec<Check> {
+    select(year, day)
+        .flat_map(|d| match std::fs::read_to_string(d.input_path()) {
+            Ok(input) => d.check(manifest, &input),
+            Err(err) => [1, 2]
+                .into_iter()
+                .filter(|part| d.part(*part).is_some())
+                .map(|part| Check {
+                    year: d.year,
+                    day: d.day,
+                    part,
+                    outcome: Outcome::NoInput(err.to_string()),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Benchmarks every part of the selected days on their `input.txt`, printing the timings as they
+/// come in. Days without an input are skipped.
+pub fn bench(
+    year: Option<u32>,
+    day: Option<u32>,
+    options: &BenchOptions,
+) -> anyhow::Result<Vec<Measurement>> {
+    let mut measurements = Vec::new();
+    for d in select(year, day) {
+        let Ok(input) = std::fs::read_to_string(d.input_path()) else {
+            println!("{} day {}: no input.txt, skipping", d.year, d.day);
+            continue;
+        };
+        for part in [1, 2] {
+            let Some(solve) = d.part(part) else {
+                continue;
+            };
+            let stats = aoc_common::bench(solve, &input, options)
+                .with_context(|| format!("{} day {} part {part}", d.year, d.day))?;
+            println!("{} day {:>2} part {part}: {stats}", d.year, d.day);
+            measurements.push(Measurement {
+                year: d.year,
+                day: d.day,
+                part,
+                stats,
+            });
+        }
+    }
+    Ok(measurements)
+}
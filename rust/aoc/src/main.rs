@@ -0,0 +1,322 @@
+use anyhow::{anyhow, bail};
+use aoc::{find_day, Agreement, Dune, Fetched, Fetcher, Submission, Submitter, Verdict};
+use aoc_common::{
+    BenchOptions, ImageArgs, InputArgs, Manifest, Outcome, OutputArgs, ParallelArgs, ParamArgs,
+    VisualizeArgs,
+};
+use clap::{Parser, Subcommand};
+use std::{collections::BTreeSet, path::PathBuf, process::ExitCode, time::Duration};
+
+#[derive(Parser)]
+#[command(about = "Run Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solution for a single day
+    Run {
+        year: u32,
+        day: u32,
+        /// Only solve this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        visualize: VisualizeArgs,
+        #[command(flatten)]
+        image: ImageArgs,
+        #[command(flatten)]
+        parallel: ParallelArgs,
+        #[command(flatten)]
+        params: ParamArgs,
+    },
+    /// Check solutions against the recorded answers in `answers.txt`
+    Check {
+        year: Option<u32>,
+        day: Option<u32>,
+        /// Record the answers of parts that have none yet, as unconfirmed until they're submitted
+        #[arg(long)]
+        record: bool,
+    },
+    /// Time the solutions over repeated runs on their `input.txt`
+    Bench {
+        year: Option<u32>,
+        day: Option<u32>,
+        /// Run each part at most this many times
+        #[arg(long, default_value_t = 10)]
+        runs: usize,
+        /// Stop repeating a part once it has taken this many seconds in total
+        #[arg(long, value_name = "SECONDS", default_value_t = 5.0)]
+        budget: f64,
+        /// Append the timings to this tab separated report [default: bench.tsv in the workspace]
+        #[arg(long, value_name = "PATH")]
+        report: Option<PathBuf>,
+    },
+    /// Run the OCaml solutions of the days solved in both languages on the same inputs, and
+    /// compare their answers with the Rust ones
+    Compare {
+        year: Option<u32>,
+        day: Option<u32>,
+        /// Compare on the worked examples as well as `input.txt`
+        #[arg(long)]
+        examples: bool,
+        /// The dune to build and run the OCaml solutions with
+        #[arg(long, value_name = "PATH", default_value = "dune")]
+        dune: PathBuf,
+    },
+    /// Download the puzzle inputs that aren't cached yet, using the session token in $AOC_SESSION
+    Fetch { year: u32, day: Option<u32> },
+    /// Solve a part and post the answer, using the session token in $AOC_SESSION. Answers the site
+    /// confirms are recorded in `answers.txt` and never submitted again
+    Submit {
+        year: u32,
+        day: u32,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Submit this answer instead of solving the part
+        #[arg(long)]
+        answer: Option<String>,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Create the files for a new day and register it with its year, adding the year if needed
+    Scaffold { year: u32, day: u32 },
+}
+
+fn run(
+    year: u32,
+    day: u32,
+    part: Option<u8>,
+    input: &InputArgs,
+    output: &OutputArgs,
+    params: &ParamArgs,
+) -> anyhow::Result<()> {
+    let day = find_day(year, day)?;
+    if params.list_params {
+        print!("{}", aoc_common::params::describe(day));
+        return Ok(());
+    }
+    params.install(day)?;
+    let input = day.read_input(input)?;
+    aoc_common::run(day, &input, part, output.format)
+}
+
+fn check(year: Option<u32>, day: Option<u32>, record: bool) -> anyhow::Result<()> {
+    let path = aoc_common::answers_path();
+    let mut manifest = Manifest::load(&path)?;
+    let checks = aoc::check(&manifest, year, day);
+
+    let mut failures = 0;
+    let mut unrecorded = BTreeSet::new();
+    for check in &checks {
+        println!("{check}");
+        match &check.outcome {
+            Outcome::Correct | Outcome::NoInput(_) => (),
+            Outcome::Wrong { .. } | Outcome::Failed(_) => failures += 1,
+            Outcome::Unrecorded(answer) => {
+                unrecorded.insert((check.year, check.day));
+                if record {
+                    manifest.record(check.year, check.day, check.part, answer.clone());
+                }
+            }
+        }
+    }
+
+    if !unrecorded.is_empty() {
+        let days = unrecorded.iter().map(|(year, day)| format!("{year}/{day}"));
+        let verb = if record { "recorded" } else { "no recorded" };
+        println!(
+            "{verb} answers for: {}",
+            days.collect::<Vec<_>>().join(", ")
+        );
+        if record {
+            manifest.save(&path)?;
+        }
+    }
+
+    match failures {
+        0 => Ok(()),
+        n => Err(anyhow!("{n} of {} parts failed", checks.len())),
+    }
+}
+
+fn bench(
+    year: Option<u32>,
+    day: Option<u32>,
+    options: &BenchOptions,
+    report: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let measurements = aoc::bench(year, day, options)?;
+    let report = report.unwrap_or_else(aoc_common::bench_report_path);
+    aoc_common::append_report(&report, &measurements)?;
+    println!("report written to {}", report.display());
+    Ok(())
+}
+
+fn compare(
+    year: Option<u32>,
+    day: Option<u32>,
+    examples: bool,
+    dune: PathBuf,
+) -> anyhow::Result<()> {
+    let runner = Dune {
+        program: dune,
+        ..Dune::default()
+    };
+    let comparisons = aoc::compare(&runner, year, day, examples);
+    if comparisons.is_empty() {
+        bail!("no days solved in both Rust and OCaml were selected");
+    }
+
+    let mut failures = 0;
+    for comparison in &comparisons {
+        println!("{comparison}");
+        match comparison.agreement {
+            Agreement::Same(_) | Agreement::NoOcamlAnswer(_) => (),
+            Agreement::Different { .. } | Agreement::Failed(_) => failures += 1,
+        }
+    }
+    match failures {
+        0 => Ok(()),
+        n => Err(anyhow!(
+            "{n} of {} parts disagree or failed",
+            comparisons.len()
+        )),
+    }
+}
+
+fn fetch(year: u32, day: Option<u32>) -> anyhow::Result<()> {
+    let days: Vec<_> = aoc::select(Some(year), day).collect();
+    if days.is_empty() {
+        let day = day.map(|day| format!(" day {day}")).unwrap_or_default();
+        bail!("no solution for {year}{day}, create it with `aoc scaffold` first");
+    }
+
+    let mut fetcher = Fetcher::from_env()?;
+    for d in days {
+        match fetcher.fetch(d)? {
+            Fetched::Cached => println!("{} day {}: already cached", d.year, d.day),
+            Fetched::Downloaded => {
+                println!(
+                    "{} day {}: saved {}",
+                    d.year,
+                    d.day,
+                    d.input_path().display()
+                )
+            }
+        }
+    }
+    Ok(())
+}
+
+fn submit(
+    year: u32,
+    day: u32,
+    part: u8,
+    answer: Option<String>,
+    input: &InputArgs,
+) -> anyhow::Result<()> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let d = find_day(year, day)?;
+            let answer = d.solve(part, &d.read_input(input)?)?.answer;
+            if answer.contains('\n') {
+                bail!(
+                    "the answer is drawn over several lines, read it off and pass it with \
+                     --answer:\n{answer}"
+                );
+            }
+            answer
+        }
+    };
+
+    let path = aoc_common::answers_path();
+    let mut manifest = Manifest::load(&path)?;
+    let submitter = Submitter::from_env()?;
+    match submitter.submit(year, day, part, &answer, &mut manifest)? {
+        Submission::Known { recorded } if recorded == answer => {
+            println!("{year} day {day} part {part}: {answer} is already recorded as correct");
+        }
+        Submission::Known { recorded } => bail!(
+            "{year} day {day} part {part} is already solved with {recorded}, not submitting {answer}"
+        ),
+        Submission::Sent(Verdict::Correct) => {
+            manifest.save(&path)?;
+            println!("{year} day {day} part {part}: {answer} is correct, recorded");
+        }
+        Submission::Sent(verdict) => bail!("{year} day {day} part {part}: {answer}: {verdict}"),
+    }
+    Ok(())
+}
+
+fn scaffold(year: u32, day: u32) -> anyhow::Result<()> {
+    let day_dir = aoc::scaffold(aoc_common::workspace_dir(), year, day)?;
+    println!("created {}", day_dir.display());
+    println!(
+        "save the puzzle input as {} and the example as {}",
+        day_dir.join("input.txt").display(),
+        day_dir.join("example.txt").display()
+    );
+    Ok(())
+}
+
+fn dispatch(command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::Run {
+            year,
+            day,
+            part,
+            input,
+            output,
+            visualize,
+            image,
+            parallel,
+            params,
+        } => {
+            visualize.install();
+            image.install();
+            parallel.install();
+            run(year, day, part, &input, &output, &params)
+        }
+        Command::Check { year, day, record } => check(year, day, record),
+        Command::Bench {
+            year,
+            day,
+            runs,
+            budget,
+            report,
+        } => {
+            let options = BenchOptions {
+                runs,
+                budget: Duration::try_from_secs_f64(budget)?,
+            };
+            bench(year, day, &options, report)
+        }
+        Command::Compare {
+            year,
+            day,
+            examples,
+            dune,
+        } => compare(year, day, examples, dune),
+        Command::Fetch { year, day } => fetch(year, day),
+        Command::Submit {
+            year,
+            day,
+            part,
+            answer,
+            input,
+        } => submit(year, day, part, answer, &input),
+        Command::Scaffold { year, day } => scaffold(year, day),
+    }
+}
+
+fn main() -> ExitCode {
+    aoc_common::report(dispatch(Cli::parse().command))
+}
@@ -0,0 +1,386 @@
+use anyhow::{bail, Context};
+use aoc_common::Day;
+use std::{
+    fmt::Display,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+/// The directory holding the OCaml solutions, as `<year>/<day>/main.ml`.
+pub fn ocaml_dir() -> PathBuf {
+    aoc_common::workspace_dir()
+        .parent()
+        .unwrap_or(aoc_common::workspace_dir())
+        .join("ocaml")
+}
+
+/// Runs the OCaml solutions, so tests can stand in for the OCaml toolchain.
+pub trait OcamlRunner {
+    /// Whether there is an OCaml solution for `year` and `day`.
+    fn has_solution(&self, year: u32, day: u32) -> bool;
+
+    /// Runs the OCaml solution for `year` and `day` with `input` on stdin, returning its stdout.
+    fn run(&self, year: u32, day: u32, input: &str) -> anyhow::Result<String>;
+}
+
+/// Builds and runs the OCaml solutions with `dune exec`, from each year's directory.
+pub struct Dune {
+    /// The `dune` executable
+    pub program: PathBuf,
+    /// See [`ocaml_dir`]
+    pub root: PathBuf,
+}
+
+impl Default for Dune {
+    fn default() -> Self {
+        Self {
+            program: "dune".into(),
+            root: ocaml_dir(),
+        }
+    }
+}
+
+impl OcamlRunner for Dune {
+    fn has_solution(&self, year: u32, day: u32) -> bool {
+        // `dune exec --root .` only builds anything from a year with a `dune-project`
+        let year_dir = self.root.join(year.to_string());
+        year_dir.join("dune-project").exists() && year_dir.join(format!("{day}/main.ml")).exists()
+    }
+
+    fn run(&self, year: u32, day: u32, input: &str) -> anyhow::Result<String> {
+        let mut child = Command::new(&self.program)
+            .args(["exec", "--root", ".", "--display", "quiet"])
+            .arg(format!("{day}/main.exe"))
+            .current_dir(self.root.join(year.to_string()))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("running {}", self.program.display()))?;
+
+        // Feed stdin from another thread, so a solution that prints before it has read all of
+        // its input can't fill up stdout and wait on us forever
+        let mut stdin = child.stdin.take().unwrap();
+        let input = input.to_owned();
+        let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let output = child.wait_with_output()?;
+        // A solution may exit without reading all of its input, that's not our problem
+        let _ = writer.join();
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("{year} day {day} {}: {}", output.status, stderr.trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Picks the answers out of what an OCaml solution printed. The solutions label each part with
+/// some variation of `Part 1:`, followed by the answer or by details ending in the answer, so
+/// the answer is the last number before the next part's label.
+pub fn parse_answers(stdout: &str) -> [Option<String>; 2] {
+    let lower = stdout.to_ascii_lowercase();
+    let mut labels = Vec::new();
+    for (idx, _) in lower.match_indices("part") {
+        let rest = &lower[idx + 4..];
+        let digits = rest.trim_start();
+        let skipped = rest.len() - digits.len();
+        let part = match digits.as_bytes() {
+            [b'1', next, ..] | [b'2', next, ..] if next.is_ascii_digit() => continue,
+            [b'1', ..] => 1,
+            [b'2', ..] => 2,
+            _ => continue,
+        };
+        labels.push((part, idx, idx + 4 + skipped + 1));
+    }
+
+    let mut answers = [None, None];
+    for (n, (part, _, start)) in labels.iter().enumerate() {
+        let end = labels.get(n + 1).map_or(stdout.len(), |(_, idx, _)| *idx);
+        let last_number = stdout[*start..end]
+            .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+            .rfind(|word| word.trim_start_matches('-').parse::<u64>().is_ok());
+        if let Some(number) = last_number {
+            answers[part - 1] = Some(number.to_owned());
+        }
+    }
+    answers
+}
+
+/// How the Rust and OCaml answers to one part compare.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Agreement {
+    Same(String),
+    Different {
+        rust: String,
+        ocaml: String,
+    },
+    /// The OCaml solution ran, but printed nothing that looks like an answer to the part
+    NoOcamlAnswer(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    /// The file name of the input both solutions ran on
+    pub input: String,
+    pub agreement: Agreement,
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Comparison {
+            year,
+            day,
+            part,
+            input,
+            ..
+        } = self;
+        write!(f, "{year} day {day} part {part} ({input}): ")?;
+        match &self.agreement {
+            Agreement::Same(answer) => write!(f, "ok, both answer {answer:?}"),
+            Agreement::Different { rust, ocaml } => {
+                write!(f, "DIFFERENT, rust answers {rust:?}, ocaml {ocaml:?}")
+            }
+            Agreement::NoOcamlAnswer(rust) => {
+                write!(f, "no answer in the OCaml output (rust answers {rust:?})")
+            }
+            Agreement::Failed(err) => write!(f, "FAILED: {err}"),
+        }
+    }
+}
+
+/// Runs both solutions for `day` on `input`, the contents of the file called `name`, and
+/// compares their answers part by part.
+pub fn compare_day(
+    runner: &impl OcamlRunner,
+    day: &Day,
+    name: &str,
+    input: &str,
+) -> Vec<Comparison> {
+    let ocaml = runner
+        .run(day.year, day.day, input)
+        .map(|out| parse_answers(&out));
+    [1, 2]
+        .into_iter()
+        .filter_map(|part| Some((part, day.part(part)?)))
+        .map(|(part, solve)| {
+            let agreement = match (solve(input), &ocaml) {
+                (Err(err), _) => Agreement::Failed(format!("rust: {err:#}")),
+                (_, Err(err)) => Agreement::Failed(format!("ocaml: {err:#}")),
+                (Ok(rust), Ok(answers)) => match &answers[usize::from(part) - 1] {
+                    None => Agreement::NoOcamlAnswer(rust),
+                    Some(ocaml) if *ocaml == rust => Agreement::Same(rust),
+                    Some(ocaml) => Agreement::Different {
+                        rust,
+                        ocaml: ocaml.clone(),
+                    },
+                },
+            };
+            Comparison {
+                year: day.year,
+                day: day.day,
+                part,
+                input: name.to_owned(),
+                agreement,
+            }
+        })
+        .collect()
+}
+
+/// The inputs to compare `day` on: its `input.txt`, and its examples too with `examples`.
+fn inputs(day: &Day, examples: bool) -> Vec<PathBuf> {
+    let mut paths = vec![day.input_path()];
+    if examples {
+        let mut found: Vec<PathBuf> = std::fs::read_dir(day.source_dir())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                let name = file_name(path);
+                name.starts_with("example") && name.ends_with(".txt")
+            })
+            .collect();
+        found.sort();
+        paths.extend(found);
+    }
+    paths
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+}
+
+/// Compares the Rust and OCaml solutions of every selected day that has both, on the same input
+/// files. Inputs that can't be read are reported as failures.
+pub fn compare(
+    runner: &impl OcamlRunner,
+    year: Option<u32>,
+    day: Option<u32>,
+    examples: bool,
+) -> Vec<Comparison> {
+    crate::select(year, day)
+        .filter(|d| runner.has_solution(d.year, d.day))
+        .flat_map(|d| {
+            inputs(d, examples).into_iter().flat_map(move |path| {
+                let name = file_name(&path);
+                match std::fs::read_to_string(&path) {
+                    Ok(input) => compare_day(runner, d, &name, &input),
+                    Err(err) => vec![Comparison {
+                        year: d.year,
+                        day: d.day,
+                        part: 1,
+                        input: name,
+                        agreement: Agreement::Failed(format!("reading {}: {err}", path.display())),
+                    }],
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prints `stdout` for every day, or fails with it when `fail` is set.
+    struct FakeRunner {
+        stdout: &'static str,
+        fail: bool,
+    }
+
+    impl OcamlRunner for FakeRunner {
+        fn has_solution(&self, year: u32, day: u32) -> bool {
+            (year, day) == (2022, 1)
+        }
+
+        fn run(&self, _: u32, _: u32, _: &str) -> anyhow::Result<String> {
+            match self.fail {
+                true => bail!("{}", self.stdout),
+                false => Ok(self.stdout.to_owned()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let some = |a: &str, b: &str| [Some(a.to_owned()), Some(b.to_owned())];
+        let outputs = [
+            // The different ways the OCaml solutions print their answers
+            (
+                "part 1: 3654868\npart 2 result: 7014\n",
+                some("3654868", "7014"),
+            ),
+            (
+                "Part 1:\noutput: 0 0 0 0 7157989\n\nPart 2:\ndiagnostic code: 7873292\n",
+                some("7157989", "7873292"),
+            ),
+            (
+                "part 1 BOOST keycode: 3235019597\npart 2 coordinates: 80274\n",
+                some("3235019597", "80274"),
+            ),
+            ("\nPart 1: 24000\nPart 2: 45000\n", some("24000", "45000")),
+            ("Part1: 37\nPart2: -168\n", some("37", "-168")),
+            (
+                "Part 1 final pos: 15, 10 (150)\nPart 2 final pos: 15, 60 (900)\n",
+                some("150", "900"),
+            ),
+        ];
+        for (stdout, answers) in outputs {
+            assert_eq!(parse_answers(stdout), answers, "{stdout}");
+        }
+        assert_eq!(
+            parse_answers("Part 1: 12\nPart 2:\n# #\n"),
+            [Some("12".to_owned()), None]
+        );
+        assert_eq!(parse_answers("counterpart 3: 4\n"), [None, None]);
+    }
+
+    #[test]
+    fn test_compare_day() {
+        let day = &advent_of_code_2022::day_1::DAY;
+        let input = std::fs::read_to_string(day.source_dir().join("example.txt")).unwrap();
+
+        let runner = FakeRunner {
+            stdout: "\nPart 1: 24000\nPart 2: 45001\n",
+            fail: false,
+        };
+        let comparisons = compare_day(&runner, day, "example.txt", &input);
+        let agreements: Vec<_> = comparisons.iter().map(|c| c.agreement.clone()).collect();
+        assert_eq!(
+            agreements,
+            [
+                Agreement::Same("24000".to_owned()),
+                Agreement::Different {
+                    rust: "45000".to_owned(),
+                    ocaml: "45001".to_owned()
+                }
+            ]
+        );
+        assert_eq!(
+            comparisons[1].to_string(),
+            "2022 day 1 part 2 (example.txt): DIFFERENT, rust answers \"45000\", ocaml \"45001\""
+        );
+
+        let runner = FakeRunner {
+            stdout: "Fatal error: exception Not_found",
+            fail: true,
+        };
+        for comparison in compare_day(&runner, day, "example.txt", &input) {
+            assert_eq!(
+                comparison.agreement,
+                Agreement::Failed("ocaml: Fatal error: exception Not_found".to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_selects_days_with_both() {
+        let runner = FakeRunner {
+            stdout: "Part 1: 1\nPart 2: 2\n",
+            fail: false,
+        };
+        let comparisons = compare(&runner, Some(2022), None, true);
+        let inputs: Vec<_> = comparisons
+            .iter()
+            .map(|c| (c.year, c.day, c.part, c.input.as_str()))
+            .collect();
+        assert_eq!(
+            inputs,
+            [
+                (2022, 1, 1, "input.txt"),
+                (2022, 1, 2, "input.txt"),
+                (2022, 1, 1, "example.txt"),
+                (2022, 1, 2, "example.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dune_needs_a_project() {
+        let root = std::env::temp_dir().join(format!("aoc-ocaml-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("2019/1")).unwrap();
+        std::fs::write(root.join("2019/1/main.ml"), "").unwrap();
+        let dune = Dune {
+            program: "dune".into(),
+            root: root.clone(),
+        };
+        assert!(!dune.has_solution(2019, 1));
+        std::fs::write(root.join("2019/dune-project"), "(lang dune 2.9)\n").unwrap();
+        assert!(dune.has_solution(2019, 1));
+        assert!(!dune.has_solution(2019, 2));
+        std::fs::remove_dir_all(&root).unwrap();
+
+        // Every year with Rust solutions to compare with can be built
+        for year in [2019, 2021, 2022, 2023] {
+            assert!(Dune::default().has_solution(year, 1), "{year}");
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use anyhow::{anyhow, bail, Context};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Creates the files for a new day in the workspace at `root` and registers it with the year's
+/// crate, creating the year's crate first if needed. Returns the new day's directory.
+pub fn scaffold(root: &Path, year: u32, day: u32) -> anyhow::Result<PathBuf> {
+    if !(1..=25).contains(&day) {
+        bail!("day must be between 1 and 25, got {day}");
+    }
+
+    let crate_dir = root.join(year.to_string());
+    if !crate_dir.exists() {
+        create_year(root, year)?;
+    }
+
+    // Older years keep their days below `src/`, newer ones right in the crate directory
+    let src_dir = if crate_dir.join("lib.rs").exists() {
+        crate_dir.clone()
+    } else {
+        crate_dir.join("src")
+    };
+    let day_dir = src_dir.join(day.to_string());
+    if day_dir.exists() {
+        bail!("{} already exists", day_dir.display());
+    }
+
+    fs::create_dir_all(&day_dir)?;
+    write(
+        &day_dir.join(format!("day_{day}.rs")),
+        &day_template(year, day),
+    )?;
+    write(&day_dir.join("main.rs"), &main_template(year, day))?;
+    write(&day_dir.join("example.txt"), "")?;
+
+    let bin_path = day_dir.join("main.rs");
+    let bin_path = bin_path.strip_prefix(&crate_dir)?.to_string_lossy();
+    edit(&crate_dir.join("Cargo.toml"), |s| {
+        Ok(format!(
+            "{}\n\n[[bin]]\nname = \"{year}_day_{day}\"\npath = \"{bin_path}\"\n",
+            s.trim_end()
+        ))
+    })?;
+    edit(&src_dir.join("lib.rs"), |s| add_day_to_lib(s, day))?;
+
+    Ok(day_dir)
+}
+
+fn create_year(root: &Path, year: u32) -> anyhow::Result<()> {
+    let crate_dir = root.join(year.to_string());
+    fs::create_dir_all(&crate_dir)?;
+    write(
+        &crate_dir.join("Cargo.toml"),
+        &format!(
+            r#"[package]
+name = "advent_of_code_{year}"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+path = "lib.rs"
+
+[dependencies]
+aoc_common = {{ path = "../common" }}
+anyhow = "1.0.93"
+"#
+        ),
+    )?;
+    write(&crate_dir.join("lib.rs"), &lib_template(&[]))?;
+
+    edit(&root.join("Cargo.toml"), |s| {
+        insert_sorted(s, year, &format!("    \"{year}\","), |line| {
+            line.trim()
+                .strip_prefix('"')?
+                .strip_suffix("\",")?
+                .parse()
+                .ok()
+        })
+    })?;
+    edit(&root.join("aoc/Cargo.toml"), |s| {
+        let line = format!("advent_of_code_{year} = {{ path = \"../{year}\" }}");
+        insert_sorted(s, year, &line, |line| {
+            let (name, _) = line.strip_prefix("advent_of_code_")?.split_once(' ')?;
+            name.parse().ok()
+        })
+    })?;
+    edit(&root.join("aoc/src/lib.rs"), |s| {
+        let line = format!("        advent_of_code_{year}::DAYS,");
+        insert_sorted(s, year, &line, |line| {
+            let line = line.trim().strip_prefix("advent_of_code_")?;
+            line.strip_suffix("::DAYS,")?.parse().ok()
+        })
+    })
+}
+
+fn write(path: &Path, contents: &str) -> anyhow::Result<()> {
+    fs::write(path, contents).with_context(|| format!("writing {}", path.display()))
+}
+
+fn edit<F>(path: &Path, f: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&str) -> anyhow::Result<String>,
+{
+    let s = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let s = f(&s).with_context(|| format!("updating {}", path.display()))?;
+    write(path, &s)
+}
+
+/// Inserts `new_line` among the lines that `key` recognises, keeping them sorted by key.
+fn insert_sorted<F>(s: &str, new_key: u32, new_line: &str, key: F) -> anyhow::Result<String>
+where
+    F: Fn(&str) -> Option<u32>,
+{
+    let mut lines: Vec<&str> = s.lines().collect();
+    let keyed: Vec<(usize, u32)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| Some((idx, key(line)?)))
+        .collect();
+    if keyed.iter().any(|(_, key)| *key == new_key) {
+        bail!("{new_key} is already listed");
+    }
+    let idx = match keyed.iter().find(|(_, key)| *key > new_key) {
+        Some((idx, _)) => *idx,
+        None => keyed.last().ok_or_else(|| anyhow!("no list to add to"))?.0 + 1,
+    };
+    lines.insert(idx, new_line);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `day` to the modules and `DAYS` of a year's `lib.rs`, leaving everything else as it is.
+fn add_day_to_lib(s: &str, day: u32) -> anyhow::Result<String> {
+    let day_of = |line: &str| -> Option<u32> {
+        line.strip_prefix("pub mod day_")?
+            .strip_suffix(';')?
+            .parse()
+            .ok()
+    };
+    let mut lines: Vec<String> = s.lines().map(String::from).collect();
+    let modules: Vec<(usize, u32)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| Some((idx, day_of(line)?)))
+        .collect();
+    if modules.iter().any(|(_, n)| *n == day) {
+        bail!("day {day} is already listed");
+    }
+
+    // Replace the whole `DAYS` statement, since rustfmt lays it out differently by length
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS"))
+        .ok_or_else(|| anyhow!("no DAYS to add to"))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line.trim_end().ends_with("];"))
+            .ok_or_else(|| anyhow!("DAYS is never closed"))?;
+    let list = lines[start..=end].join(" ");
+    let list = list
+        .split_once("= &[")
+        .and_then(|(_, rest)| rest.rsplit_once("];"))
+        .ok_or_else(|| anyhow!("can't read the DAYS list"))?
+        .0;
+    let mut entries: Vec<String> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect();
+    let entry_day = |entry: &str| -> Option<u32> {
+        entry
+            .strip_prefix("day_")?
+            .strip_suffix("::DAY")?
+            .parse()
+            .ok()
+    };
+    let at = match entries
+        .iter()
+        .position(|e| entry_day(e).is_some_and(|n| n > day))
+    {
+        Some(at) => at,
+        None => entries
+            .iter()
+            .rposition(|e| entry_day(e).is_some())
+            .map_or(entries.len(), |at| at + 1),
+    };
+    entries.insert(at, format!("day_{day}::DAY"));
+    lines.splice(start..=end, [days_const(&entries)]);
+
+    let module = format!("#[path = \"{day}/day_{day}.rs\"]\npub mod day_{day};");
+    match modules.iter().find(|(_, n)| *n > day) {
+        // Before the attribute of the next module along
+        Some((idx, _)) => lines.insert(idx - 1, format!("{module}\n")),
+        None => match modules.last() {
+            Some((idx, _)) => lines.insert(idx + 1, format!("\n{module}")),
+            None => lines.insert(start, format!("{module}\n")),
+        },
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+fn lib_template(days: &[u32]) -> String {
+    let mut s = String::new();
+    for day in days {
+        s += &format!("#[path = \"{day}/day_{day}.rs\"]\npub mod day_{day};\n\n");
+    }
+    let entries: Vec<String> = days.iter().map(|day| format!("day_{day}::DAY")).collect();
+    s + &days_const(&entries) + "\n"
+}
+
+/// The `DAYS` statement listing `entries`.
+fn days_const(entries: &[String]) -> String {
+    let one_line = format!(
+        "pub const DAYS: &[aoc_common::Day] = &[{}];",
+        entries.join(", ")
+    );
+    // Match rustfmt, which breaks the list over several lines when it gets too long
+    if entries.join(", ").len() + 2 <= 60 && one_line.len() <= 100 {
+        return one_line;
+    }
+    let mut s = "pub const DAYS: &[aoc_common::Day] = &[\n".to_string();
+    for entry in entries {
+        s += &format!("    {entry},\n");
+    }
+    s + "];"
+}
+
+fn day_template(year: u32, day: u32) -> String {
+    format!(
+        r#"fn parse(input: &str) -> anyhow::Result<Vec<&str>> {{
+    Ok(input.lines().collect())
+}}
+
+pub fn part_1(input: &str) -> anyhow::Result<usize> {{
+    let lines = parse(input)?;
+    anyhow::bail!("not solved yet ({{}} lines of input)", lines.len())
+}}
+
+pub const DAY: aoc_common::Day = aoc_common::day!({year}, {day}, part_1 only);
+
+// aoc_common::examples! {{
+//     "example.txt" => {{ part_1: 0 }},
+// }}
+"#
+    )
+}
+
+fn main_template(year: u32, day: u32) -> String {
+    format!(
+        "fn main() -> std::process::ExitCode {{
+    aoc_common::main(&advent_of_code_{year}::day_{day}::DAY)
+}}
+"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_sorted() {
+        let s = "members = [\n    \"aoc\",\n    \"2015\",\n    \"2022\",\n]\n";
+        let key = |line: &str| {
+            line.trim()
+                .strip_prefix('"')?
+                .strip_suffix("\",")?
+                .parse()
+                .ok()
+        };
+        assert_eq!(
+            insert_sorted(s, 2019, "    \"2019\",", key).unwrap(),
+            "members = [\n    \"aoc\",\n    \"2015\",\n    \"2019\",\n    \"2022\",\n]\n"
+        );
+        assert_eq!(
+            insert_sorted(s, 2024, "    \"2024\",", key).unwrap(),
+            "members = [\n    \"aoc\",\n    \"2015\",\n    \"2022\",\n    \"2024\",\n]\n"
+        );
+        assert!(insert_sorted(s, 2022, "    \"2022\",", key).is_err());
+    }
+
+    #[test]
+    fn test_add_day_to_lib() {
+        let lib = lib_template(&[1, 3]);
+        assert_eq!(
+            add_day_to_lib(&lib, 2).unwrap(),
+            "#[path = \"1/day_1.rs\"]\npub mod day_1;\n\n\
+             #[path = \"2/day_2.rs\"]\npub mod day_2;\n\n\
+             #[path = \"3/day_3.rs\"]\npub mod day_3;\n\n\
+             pub const DAYS: &[aoc_common::Day] = &[day_1::DAY, day_2::DAY, day_3::DAY];\n"
+        );
+    }
+
+    #[test]
+    fn test_add_day_to_lib_keeps_other_content() {
+        let lib = "//! Hand-written notes\n\n\
+                   #[path = \"1/day_1.rs\"]\npub mod day_1;\n\n\
+                   #[path = \"3/day_3.rs\"]\npub mod day_3;\n\n\
+                   mod shared;\n\n\
+                   pub const DAYS: &[aoc_common::Day] = &[day_1::DAY, day_3::DAY];\n\n\
+                   pub fn helper() {}\n";
+        assert_eq!(
+            add_day_to_lib(lib, 2).unwrap(),
+            "//! Hand-written notes\n\n\
+             #[path = \"1/day_1.rs\"]\npub mod day_1;\n\n\
+             #[path = \"2/day_2.rs\"]\npub mod day_2;\n\n\
+             #[path = \"3/day_3.rs\"]\npub mod day_3;\n\n\
+             mod shared;\n\n\
+             pub const DAYS: &[aoc_common::Day] = &[day_1::DAY, day_2::DAY, day_3::DAY];\n\n\
+             pub fn helper() {}\n"
+        );
+        assert_eq!(
+            add_day_to_lib(lib, 4).unwrap(),
+            "//! Hand-written notes\n\n\
+             #[path = \"1/day_1.rs\"]\npub mod day_1;\n\n\
+             #[path = \"3/day_3.rs\"]\npub mod day_3;\n\n\
+             #[path = \"4/day_4.rs\"]\npub mod day_4;\n\n\
+             mod shared;\n\n\
+             pub const DAYS: &[aoc_common::Day] = &[day_1::DAY, day_3::DAY, day_4::DAY];\n\n\
+             pub fn helper() {}\n"
+        );
+        assert!(add_day_to_lib(lib, 3).is_err());
+    }
+
+    #[test]
+    fn test_add_day_matches_template() {
+        assert_eq!(
+            add_day_to_lib(&lib_template(&[]), 1).unwrap(),
+            lib_template(&[1])
+        );
+        let days: Vec<u32> = (1..=9).collect();
+        assert_eq!(
+            add_day_to_lib(&lib_template(&days[..8]), 9).unwrap(),
+            lib_template(&days)
+        );
+    }
+
+    #[test]
+    fn test_lib_template_wraps_long_lists() {
+        let lib = lib_template(&(1..=9).collect::<Vec<_>>());
+        assert!(lib.ends_with("&[\n    day_1::DAY,\n    day_2::DAY,\n    day_3::DAY,\n    day_4::DAY,\n    day_5::DAY,\n    day_6::DAY,\n    day_7::DAY,\n    day_8::DAY,\n    day_9::DAY,\n];\n"));
+    }
+}
@@ -0,0 +1,328 @@
+use crate::fetch::{session_from_env, Client, HttpClient, BASE_URL};
+use anyhow::bail;
+use aoc_common::Manifest;
+use std::{fmt, time::Duration};
+
+/// What the site made of a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    /// Wrong, without a hint which way
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the previous answer, with the time left to wait if the site said
+    RateLimited(Option<Duration>),
+    /// The part was already solved, or isn't unlocked yet
+    AlreadySolved,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "that's the right answer"),
+            Verdict::Wrong => write!(f, "that's not the right answer"),
+            Verdict::TooHigh => write!(f, "that's not the right answer, it's too high"),
+            Verdict::TooLow => write!(f, "that's not the right answer, it's too low"),
+            Verdict::RateLimited(Some(wait)) => {
+                write!(f, "answered too recently, wait {}s", wait.as_secs())
+            }
+            Verdict::RateLimited(None) => write!(f, "answered too recently"),
+            Verdict::AlreadySolved => write!(f, "already solved, or not unlocked yet"),
+        }
+    }
+}
+
+/// The `<article>` holding the site's reply, or the whole page if there is none.
+fn article_text(html: &str) -> &str {
+    let start = html.find("<article").unwrap_or(0);
+    let end = html[start..]
+        .find("</article>")
+        .map_or(html.len(), |n| start + n);
+    &html[start..end]
+}
+
+/// The wait in a reply like "You have 1m 30s left to wait."
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    wait.split_whitespace()
+        .try_fold(Duration::ZERO, |total, n| {
+            let (n, unit) = n.split_at(n.find(|c: char| !c.is_ascii_digit())?);
+            let n: u64 = n.parse().ok()?;
+            let secs = match unit {
+                "s" => n,
+                "m" => n * 60,
+                "h" => n * 60 * 60,
+                _ => return None,
+            };
+            Some(total + Duration::from_secs(secs))
+        })
+}
+
+/// Reads the verdict from the page the site answers a submission with.
+pub fn parse_response(html: &str) -> anyhow::Result<Verdict> {
+    let text = article_text(html);
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::RateLimited(parse_wait(text))
+    } else if text.contains("That's not the right answer") {
+        if text.contains("too high") {
+            Verdict::TooHigh
+        } else if text.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else {
+        bail!("unexpected response to the submission: {}", text.trim());
+    };
+    Ok(verdict)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    Sent(Verdict),
+    /// The part already has an answer the site confirmed, so nothing was sent
+    Known {
+        recorded: String,
+    },
+}
+
+/// Posts answers to the site, recording the ones it confirms so a part is never solved twice.
+pub struct Submitter<C> {
+    client: C,
+    base_url: String,
+    session: String,
+}
+
+impl Submitter<HttpClient> {
+    /// A submitter for the real site, using the session token in [`crate::SESSION_VAR`].
+    pub fn from_env() -> anyhow::Result<Self> {
+        Ok(Self::new(
+            HttpClient::default(),
+            BASE_URL,
+            &session_from_env()?,
+        ))
+    }
+}
+
+impl<C: Client> Submitter<C> {
+    pub fn new(client: C, base_url: &str, session: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+        }
+    }
+
+    pub fn answer_url(&self, year: u32, day: u32) -> String {
+        format!("{}/{year}/day/{day}/answer", self.base_url)
+    }
+
+    /// Submits `answer` for `part` of `year` and `day`, unless `manifest` already has an answer
+    /// the site confirmed for it. Answers that were only recorded locally are still sent. A correct
+    /// answer is confirmed in `manifest`, it's up to the caller to save it.
+    pub fn submit(
+        &self,
+        year: u32,
+        day: u32,
+        part: u8,
+        answer: &str,
+        manifest: &mut Manifest,
+    ) -> anyhow::Result<Submission> {
+        if let Some(recorded) = manifest.get_confirmed(year, day, part) {
+            return Ok(Submission::Known {
+                recorded: recorded.to_owned(),
+            });
+        }
+
+        let level = part.to_string();
+        let form = [("level", level.as_str()), ("answer", answer)];
+        let html = self
+            .client
+            .post(&self.answer_url(year, day), &self.session, &form)?;
+        let verdict = parse_response(&html)?;
+        if verdict == Verdict::Correct {
+            manifest.confirm(year, day, part, answer.to_owned());
+        }
+        Ok(Submission::Sent(verdict))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_server::FakeServer;
+    use std::cell::RefCell;
+
+    fn page(article: &str) -> String {
+        format!("<html><body><main>\n<article><p>{article}</p></article>\n</main></body></html>")
+    }
+
+    /// Records the forms it gets and answers each with `body`.
+    struct FakeClient {
+        body: String,
+        forms: RefCell<Vec<Vec<(String, String)>>>,
+    }
+
+    impl Client for &FakeClient {
+        fn get(&self, _: &str, _: &str) -> anyhow::Result<String> {
+            unreachable!("submitting only POSTs")
+        }
+
+        fn post(&self, _: &str, _: &str, form: &[(&str, &str)]) -> anyhow::Result<String> {
+            let form = form.iter().map(|(k, v)| (k.to_string(), v.to_string()));
+            self.forms.borrow_mut().push(form.collect());
+            Ok(self.body.clone())
+        }
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let cases = [
+            (
+                "That's the right answer! You are one gold star closer to saving Christmas.",
+                Verdict::Correct,
+            ),
+            (
+                "That's not the right answer; your answer is too high. Please wait one minute \
+                 before trying again.",
+                Verdict::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                Verdict::TooLow,
+            ),
+            (
+                "That's not the right answer. If you're stuck, make sure you're using the full \
+                 input data.",
+                Verdict::Wrong,
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again. You have 1m 30s left to wait.",
+                Verdict::RateLimited(Some(Duration::from_secs(90))),
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again. You have 38s left to wait.",
+                Verdict::RateLimited(Some(Duration::from_secs(38))),
+            ),
+            (
+                "You gave an answer too recently.",
+                Verdict::RateLimited(None),
+            ),
+            (
+                "You don't seem to be solving the right level. Did you already complete it?",
+                Verdict::AlreadySolved,
+            ),
+        ];
+        for (article, verdict) in cases {
+            assert_eq!(
+                parse_response(&page(article)).unwrap(),
+                verdict,
+                "{article}"
+            );
+        }
+        assert!(parse_response(&page("Puzzle inputs differ by user.")).is_err());
+    }
+
+    #[test]
+    fn test_submit_records_correct_answers() {
+        let client = FakeClient {
+            body: page("That's the right answer!"),
+            forms: RefCell::new(Vec::new()),
+        };
+        let submitter = Submitter::new(&client, "http://localhost", "abc");
+        let mut manifest = Manifest::default();
+
+        let submission = submitter.submit(2022, 6, 2, "19", &mut manifest).unwrap();
+        assert_eq!(submission, Submission::Sent(Verdict::Correct));
+        assert_eq!(manifest.get_confirmed(2022, 6, 2), Some("19"));
+
+        let submission = submitter.submit(2022, 6, 2, "19", &mut manifest).unwrap();
+        assert_eq!(
+            submission,
+            Submission::Known {
+                recorded: "19".to_owned()
+            }
+        );
+        let level = |l: &str| vec![("level".into(), l.into()), ("answer".into(), "19".into())];
+        assert_eq!(*client.forms.borrow(), vec![level("2")]);
+    }
+
+    #[test]
+    fn test_submit_wrong_answers_are_not_recorded() {
+        let client = FakeClient {
+            body: page("That's not the right answer; your answer is too low."),
+            forms: RefCell::new(Vec::new()),
+        };
+        let submitter = Submitter::new(&client, "http://localhost", "abc");
+        let mut manifest = Manifest::default();
+
+        for _ in 0..2 {
+            let submission = submitter.submit(2022, 6, 1, "5", &mut manifest).unwrap();
+            assert_eq!(submission, Submission::Sent(Verdict::TooLow));
+        }
+        assert_eq!(manifest.get(2022, 6, 1), None);
+        assert_eq!(client.forms.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_submit_over_http() {
+        let server = FakeServer::start(vec![
+            (
+                "200 OK",
+                page("You gave an answer too recently. You have 5s left to wait."),
+            ),
+            ("200 OK", page("That's the right answer!")),
+        ]);
+        let submitter = Submitter::new(HttpClient::default(), &server.url, "abc");
+        let mut manifest = Manifest::default();
+
+        let submission = submitter.submit(2024, 7, 1, "3749", &mut manifest);
+        assert_eq!(
+            submission.unwrap(),
+            Submission::Sent(Verdict::RateLimited(Some(Duration::from_secs(5))))
+        );
+        let submission = submitter.submit(2024, 7, 1, "3749", &mut manifest);
+        assert_eq!(submission.unwrap(), Submission::Sent(Verdict::Correct));
+        assert_eq!(manifest.get_confirmed(2024, 7, 1), Some("3749"));
+
+        for request in server.requests() {
+            assert!(request
+                .head
+                .starts_with("POST /2024/day/7/answer HTTP/1.1\r\n"));
+            assert_eq!(request.header("cookie"), Some("session=abc"));
+            assert_eq!(request.body, "level=1&answer=3749");
+        }
+    }
+
+    #[test]
+    fn test_submit_sends_unconfirmed_answers() {
+        let server = FakeServer::start(vec![("200 OK", page("That's the right answer!"))]);
+        let submitter = Submitter::new(HttpClient::default(), &server.url, "abc");
+        // As left by `aoc check --record`
+        let mut manifest: Manifest = "2024 7 1? 3749\n".parse().unwrap();
+
+        let submission = submitter.submit(2024, 7, 1, "3749", &mut manifest);
+        assert_eq!(submission.unwrap(), Submission::Sent(Verdict::Correct));
+        assert_eq!(manifest.get_confirmed(2024, 7, 1), Some("3749"));
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body, "level=1&answer=3749");
+
+        // Now that the site confirmed it, it isn't sent again
+        let submission = submitter.submit(2024, 7, 1, "3749", &mut manifest);
+        assert_eq!(
+            submission.unwrap(),
+            Submission::Known {
+                recorded: "3749".to_owned()
+            }
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use aoc_common::{Manifest, Outcome};
+
+/// Runs every solved day against its `input.txt` and compares with the answers in `answers.txt`.
+/// Parts without a recorded answer or an input are listed but don't fail the test; record answers
+/// with `aoc check --record`.
+#[test]
+fn test_recorded_answers() {
+    let manifest = Manifest::load(&aoc_common::answers_path()).unwrap();
+
+    let checks: Vec<_> = std::thread::scope(|s| {
+        let handles: Vec<_> = aoc::solutions()
+            .map(|d| s.spawn(|| aoc::check(&manifest, Some(d.year), Some(d.day))))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    for check in &checks {
+        if let Outcome::Unrecorded(_) | Outcome::NoInput(_) = check.outcome {
+            println!("{check}");
+        }
+    }
+
+    let failures: Vec<_> = checks
+        .iter()
+        .filter(|check| matches!(check.outcome, Outcome::Wrong { .. } | Outcome::Failed(_)))
+        .map(|check| check.to_string())
+        .collect();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
@@ -0,0 +1,90 @@
+//! Feeds every day's parser input it wasn't written for, checking that it answers with an error
+//! rather than panicking. `fuzz/` runs the same check under libFuzzer for longer.
+
+use aoc_common::ParseFn;
+use proptest::{prelude::*, sample::Index};
+
+fn parsers() -> Vec<ParseFn> {
+    aoc::solutions().filter_map(|d| d.parse).collect()
+}
+
+/// The worked examples, to mutate into input that gets past the first line of the parsers.
+fn examples() -> Vec<String> {
+    aoc::solutions()
+        .filter(|d| d.parse.is_some())
+        .flat_map(|d| std::fs::read_dir(d.source_dir()).unwrap())
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with("example") && name.ends_with(".txt")
+        })
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect()
+}
+
+/// The characters puzzle inputs are made of.
+fn puzzle_char() -> impl Strategy<Value = char> {
+    prop::sample::select(
+        "0123456789-+abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ #.^<>[],:;=$/()\n"
+            .chars()
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn mutated_example() -> impl Strategy<Value = String> {
+    let edit = (any::<Index>(), 0..3u8, puzzle_char());
+    (any::<Index>(), prop::collection::vec(edit, 1..8)).prop_map(|(example, edits)| {
+        let examples = examples();
+        let mut chars: Vec<char> = example.get(&examples).chars().collect();
+        for (idx, kind, c) in edits {
+            let idx = idx.index(chars.len() + 1);
+            match kind {
+                0 if idx < chars.len() => drop(chars.remove(idx)),
+                1 if idx < chars.len() => chars[idx] = c,
+                _ => chars.insert(idx, c),
+            }
+        }
+        chars.into_iter().collect()
+    })
+}
+
+fn parse_all(input: &str) {
+    for parse in parsers() {
+        let _ = parse(input);
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_arbitrary_text(input in any::<String>()) {
+        parse_all(&input);
+    }
+
+    #[test]
+    fn test_puzzle_like_text(chars in prop::collection::vec(puzzle_char(), 0..300)) {
+        parse_all(&String::from_iter(chars));
+    }
+
+    #[test]
+    fn test_mutated_examples(input in mutated_example()) {
+        parse_all(&input);
+    }
+}
+
+#[test]
+fn test_degenerate_inputs() {
+    for input in [
+        "",
+        "\n",
+        "\n\n\n",
+        "\u{feff}",
+        &"[".repeat(100_000),
+        &"9".repeat(100),
+        "-9223372036854775808",
+        "4294967295\n4294967295\n",
+        "4294967296,4294967296 -> 0,0",
+        "0,0 -> 100000,0 -> 100000,100000",
+    ] {
+        parse_all(input);
+    }
+}
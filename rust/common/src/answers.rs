@@ -0,0 +1,255 @@
+use crate::Day;
+use anyhow::{anyhow, Context};
+use std::{collections::BTreeMap, fmt::Display, path::Path, str::FromStr};
+
+/// The first line of a manifest in the current format. Manifests without it are from before the
+/// site's confirmations were kept, so all their answers count as only recorded locally, and only
+/// their newlines are escaped.
+const HEADER: &str = "# answers v2";
+
+/// The known correct answers, keyed by year, day and part.
+///
+/// Stored as [`HEADER`] and then one `year day part answer` line per answer. Newlines in
+/// multi-line answers (like a rendered CRT) are written as `\n` and backslashes as `\\`, and
+/// lines starting with `#` are comments. Answers that were only recorded locally, and never
+/// confirmed by the site, have a `?` after the part.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    answers: BTreeMap<(u32, u32, u8), Answer>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Answer {
+    text: String,
+    /// Whether the site said this was the right answer
+    confirmed: bool,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        std::fs::read_to_string(path)
+            .with_context(|| format!("reading {}", path.display()))?
+            .parse()
+            .with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// The answer for the part, whether or not the site confirmed it.
+    pub fn get(&self, year: u32, day: u32, part: u8) -> Option<&str> {
+        self.answers
+            .get(&(year, day, part))
+            .map(|answer| answer.text.as_str())
+    }
+
+    /// The answer for the part, if the site confirmed it.
+    pub fn get_confirmed(&self, year: u32, day: u32, part: u8) -> Option<&str> {
+        self.answers
+            .get(&(year, day, part))
+            .filter(|answer| answer.confirmed)
+            .map(|answer| answer.text.as_str())
+    }
+
+    /// Records an answer the site hasn't confirmed, like one a solution came up with.
+    pub fn record(&mut self, year: u32, day: u32, part: u8, answer: String) {
+        let answer = Answer {
+            text: answer,
+            confirmed: false,
+        };
+        self.answers.insert((year, day, part), answer);
+    }
+
+    /// Records an answer the site said was right.
+    pub fn confirm(&mut self, year: u32, day: u32, part: u8, answer: String) {
+        let answer = Answer {
+            text: answer,
+            confirmed: true,
+        };
+        self.answers.insert((year, day, part), answer);
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let current = s.lines().next() == Some(HEADER);
+        let mut answers = BTreeMap::new();
+        for (n, line) in s.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(4, ' ');
+            let mut field = |name| {
+                fields
+                    .next()
+                    .ok_or_else(|| anyhow!("line {}: missing {name}", n + 1))
+            };
+            let year = field("year")?.parse()?;
+            let day = field("day")?.parse()?;
+            let part = field("part")?;
+            let (part, confirmed) = match part.strip_suffix('?') {
+                Some(part) => (part, false),
+                None => (part, current),
+            };
+            let part = part.parse()?;
+            let text = field("answer")?;
+            let text = if current {
+                unescape(text).map_err(|err| anyhow!("line {}: {err}", n + 1))?
+            } else {
+                text.replace("\\n", "\n")
+            };
+            answers.insert((year, day, part), Answer { text, confirmed });
+        }
+        Ok(Self { answers })
+    }
+}
+
+impl Display for Manifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        writeln!(f, "# year day part answer")?;
+        for ((year, day, part), answer) in &self.answers {
+            let mark = if answer.confirmed { "" } else { "?" };
+            let text = answer.text.replace('\\', "\\\\").replace('\n', "\\n");
+            writeln!(f, "{year} {day} {part}{mark} {text}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reverses the escaping of backslashes and newlines in an answer.
+fn unescape(text: &str) -> Result<String, String> {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('n') => unescaped.push('\n'),
+            Some(c) => return Err(format!("unknown escape `\\{c}`")),
+            None => return Err("a `\\` at the end of the answer".to_owned()),
+        }
+    }
+    Ok(unescaped)
+}
+
+/// The result of checking one part of a day against the [`Manifest`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Correct,
+    Wrong {
+        expected: String,
+        actual: String,
+    },
+    /// The solution ran, but there is no recorded answer to compare with
+    Unrecorded(String),
+    /// The day's input couldn't be read, so it wasn't solved
+    NoInput(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub outcome: Outcome,
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Check {
+            year, day, part, ..
+        } = self;
+        write!(f, "{year} day {day} part {part}: ")?;
+        match &self.outcome {
+            Outcome::Correct => write!(f, "ok"),
+            Outcome::Wrong { expected, actual } => {
+                write!(f, "WRONG, expected {expected:?}, got {actual:?}")
+            }
+            Outcome::Unrecorded(actual) => write!(f, "no recorded answer (got {actual:?})"),
+            Outcome::NoInput(err) => write!(f, "no input.txt ({err})"),
+            Outcome::Failed(err) => write!(f, "FAILED: {err}"),
+        }
+    }
+}
+
+impl Day {
+    /// Solves every part of the day for `input` and compares the answers with `manifest`.
+    pub fn check(&self, manifest: &Manifest, input: &str) -> Vec<Check> {
+        [1, 2]
+            .into_iter()
+            .filter_map(|part| Some((part, self.part(part)?)))
+            .map(|(part, solve)| {
+                let outcome = match (solve(input), manifest.get(self.year, self.day, part)) {
+                    (Err(err), _) => Outcome::Failed(format!("{err:#}")),
+                    (Ok(actual), None) => Outcome::Unrecorded(actual),
+                    (Ok(actual), Some(expected)) if actual == expected => Outcome::Correct,
+                    (Ok(actual), Some(expected)) => Outcome::Wrong {
+                        expected: expected.to_owned(),
+                        actual,
+                    },
+                };
+                Check {
+                    year: self.year,
+                    day: self.day,
+                    part,
+                    outcome,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let s = "# answers v2\n# year day part answer\n2022 5 1 LBLVVTVLP\n2022 5 2? MCD\n\
+                 2022 10 2 #..\\n.#.\n2022 11 1 a\\\\nb\n";
+        let manifest: Manifest = s.parse().unwrap();
+        assert_eq!(manifest.get(2022, 5, 1), Some("LBLVVTVLP"));
+        assert_eq!(manifest.get(2022, 5, 2), Some("MCD"));
+        assert_eq!(manifest.get_confirmed(2022, 5, 2), None);
+        assert_eq!(manifest.get_confirmed(2022, 10, 2), Some("#..\n.#."));
+        assert_eq!(manifest.get(2022, 10, 1), None);
+        assert_eq!(manifest.get(2022, 11, 1), Some("a\\nb"));
+        assert_eq!(manifest.to_string(), s);
+    }
+
+    #[test]
+    fn test_manifest_before_confirmations() {
+        // Nothing was confirmed by the site back then, and only newlines were escaped
+        let s = "# year day part answer\n2022 5 1 LBLVVTVLP\n2022 5 2? MCD\n2022 10 2 #\\n\\.\n";
+        let manifest: Manifest = s.parse().unwrap();
+        assert_eq!(manifest.get(2022, 5, 1), Some("LBLVVTVLP"));
+        assert_eq!(manifest.get_confirmed(2022, 5, 1), None);
+        assert_eq!(manifest.get_confirmed(2022, 5, 2), None);
+        assert_eq!(manifest.get(2022, 10, 2), Some("#\n\\."));
+        assert_eq!(
+            manifest.to_string(),
+            "# answers v2\n# year day part answer\n2022 5 1? LBLVVTVLP\n2022 5 2? MCD\n\
+             2022 10 2? #\\n\\\\.\n"
+        );
+    }
+
+    #[test]
+    fn test_manifest_parse_error() {
+        assert!("2022 5".parse::<Manifest>().is_err());
+        assert!("2022 x 1 1".parse::<Manifest>().is_err());
+        assert!("2022 5 ? 1".parse::<Manifest>().is_err());
+        assert!("# answers v2\n2022 5 1 a\\b".parse::<Manifest>().is_err());
+        assert!("# answers v2\n2022 5 1 a\\".parse::<Manifest>().is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::Part;
+use anyhow::Context;
+use std::{
+    fmt::Display,
+    fs::OpenOptions,
+    io::Write,
+    path::Path,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// How many times to run each part when benchmarking.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    /// Stop after this many runs
+    pub runs: usize,
+    /// Stop early once the runs so far have taken this long in total. A part always runs at
+    /// least once.
+    pub budget: Duration,
+}
+
+/// Timings over repeated runs of one part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_timings(mut timings: Vec<Duration>) -> Self {
+        timings.sort();
+        Self {
+            runs: timings.len(),
+            min: timings[0],
+            median: timings[timings.len() / 2],
+            max: timings[timings.len() - 1],
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:>10.3?}  median {:>10.3?}  max {:>10.3?}  ({} runs)",
+            self.min, self.median, self.max, self.runs
+        )
+    }
+}
+
+/// Runs `part` on `input` repeatedly and collects the timings.
+pub fn bench(part: Part, input: &str, options: &BenchOptions) -> anyhow::Result<Stats> {
+    let mut timings = Vec::new();
+    let mut total = Duration::ZERO;
+    while timings.is_empty() || (timings.len() < options.runs && total < options.budget) {
+        let start = Instant::now();
+        std::hint::black_box(part(std::hint::black_box(input))?);
+        let elapsed = start.elapsed();
+        timings.push(elapsed);
+        total += elapsed;
+    }
+    Ok(Stats::from_timings(timings))
+}
+
+/// The timings of one part of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub stats: Stats,
+}
+
+const REPORT_HEADER: &str = "timestamp\tyear\tday\tpart\truns\tmin_ns\tmedian_ns\tmax_ns";
+
+/// Appends `measurements` to the tab separated report at `path`, creating it with a header if
+/// needed. Every row is stamped with the current Unix time so runs can be compared over time.
+pub fn append_report(path: &Path, measurements: &[Measurement]) -> anyhow::Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening {}", path.display()))?;
+
+    if is_new {
+        writeln!(file, "{REPORT_HEADER}")?;
+    }
+    for Measurement {
+        year,
+        day,
+        part,
+        stats,
+    } in measurements
+    {
+        writeln!(
+            file,
+            "{timestamp}\t{year}\t{day}\t{part}\t{}\t{}\t{}\t{}",
+            stats.runs,
+            stats.min.as_nanos(),
+            stats.median.as_nanos(),
+            stats.max.as_nanos()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_timings(vec![ms(5), ms(1), ms(9), ms(3), ms(4)]);
+        assert_eq!(
+            stats,
+            Stats {
+                runs: 5,
+                min: ms(1),
+                median: ms(4),
+                max: ms(9)
+            }
+        );
+    }
+
+    #[test]
+    fn test_bench_runs_at_least_once() {
+        let options = BenchOptions {
+            runs: 10,
+            budget: Duration::ZERO,
+        };
+        let stats = bench(|input| Ok(input.len().to_string()), "abc", &options).unwrap();
+        assert_eq!(stats.runs, 1);
+    }
+}
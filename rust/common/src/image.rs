@@ -0,0 +1,239 @@
+//! Pictures of the states the simulations end up in.
+//!
+//! A day calls [`export`] with a name and a closure building the [`Image`]. The image is only
+//! built and written when the binary was started with `--image-dir`, in the format chosen with
+//! `--image-format`.
+
+use anyhow::Context;
+use flate2::{write::ZlibEncoder, Compression};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// A shade of grey for `value` out of `max`, from black at 0 to white at `max`.
+    pub fn grey(value: u64, max: u64) -> Rgb {
+        let level = (value.min(max) * 255).checked_div(max).unwrap_or(0) as u8;
+        Rgb(level, level, level)
+    }
+}
+
+/// An RGB image, stored row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Paints every pixel with `f(x, y)`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> Rgb) -> Self {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Blows every pixel up into a `factor` by `factor` square, for images too small to see.
+    pub fn scaled(&self, factor: usize) -> Self {
+        Self::from_fn(self.width * factor, self.height * factor, |x, y| {
+            self.get(x / factor, y / factor)
+        })
+    }
+
+    /// Encodes the image as a binary PPM (`P6`).
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.extend(self.pixels.iter().flat_map(|Rgb(r, g, b)| [*r, *g, *b]));
+        ppm
+    }
+
+    /// Encodes the image as an 8-bit RGB PNG.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression and filtering, not interlaced
+        header.extend([8, 2, 0, 0, 0]);
+
+        // Every row starts with its filter type, 0 for none
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let mut bytes = vec![0];
+            bytes.extend(row.iter().flat_map(|Rgb(r, g, b)| [*r, *g, *b]));
+            encoder.write_all(&bytes).unwrap();
+        }
+        let data = encoder.finish().unwrap();
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &data);
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// Writes the image to `path`, in the format given by its extension.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let format = path
+            .extension()
+            .and_then(|ext| ImageFormat::from_extension(&ext.to_string_lossy()))
+            .with_context(|| format!("{}: expected a .png or .ppm file", path.display()))?;
+        let bytes = match format {
+            ImageFormat::Png => self.to_png(),
+            ImageFormat::Ppm => self.to_ppm(),
+        };
+        std::fs::write(path, bytes).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    png.extend(crc.finalize().to_be_bytes());
+}
+
+#[derive(clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Ppm,
+}
+
+impl ImageFormat {
+    fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "ppm" => Some(Self::Ppm),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Ppm => "ppm",
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct ImageArgs {
+    /// Save pictures of the final states of the days that draw them in this directory
+    #[arg(long, value_name = "DIR")]
+    pub image_dir: Option<PathBuf>,
+    /// The file format of the pictures
+    #[arg(long, value_enum, default_value_t)]
+    pub image_format: ImageFormat,
+}
+
+impl ImageArgs {
+    /// Turns on image export for the rest of the process if `--image-dir` was given.
+    pub fn install(&self) {
+        if let Some(dir) = &self.image_dir {
+            *EXPORT.lock().unwrap() = Some((dir.clone(), self.image_format));
+        }
+    }
+}
+
+static EXPORT: Mutex<Option<(PathBuf, ImageFormat)>> = Mutex::new(None);
+
+/// Saves the image `render` builds as `<name>.<format>` in the `--image-dir`, if one was given.
+pub fn export(name: &str, render: impl FnOnce() -> Image) -> anyhow::Result<()> {
+    let Some((dir, format)) = EXPORT.lock().unwrap().clone() else {
+        return Ok(());
+    };
+    std::fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+    render().save(&dir.join(format!("{name}.{}", format.extension())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::ZlibDecoder;
+    use std::io::Read;
+
+    fn checkerboard() -> Image {
+        Image::from_fn(3, 2, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgb::WHITE
+            } else {
+                Rgb(255, 0, 0)
+            }
+        })
+    }
+
+    #[test]
+    fn test_ppm() {
+        let ppm = checkerboard().to_ppm();
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n"));
+        assert_eq!(ppm.len(), 11 + 3 * 2 * 3);
+        assert_eq!(&ppm[11..17], &[255, 255, 255, 255, 0, 0]);
+    }
+
+    #[test]
+    fn test_png() {
+        let png = checkerboard().to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        // Walk the chunks, checking each one's CRC
+        let mut chunks = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (body, crc) = rest[4..].split_at(4 + len);
+            assert_eq!(crc32fast::hash(body).to_be_bytes(), crc[..4]);
+            chunks.push((&body[..4], &body[4..]));
+            rest = &crc[4..];
+        }
+        let kinds: Vec<_> = chunks.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 3, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+
+        let mut data = Vec::new();
+        ZlibDecoder::new(chunks[1].1)
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), 2 * (1 + 3 * 3));
+        assert_eq!(&data[..4], &[0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_scaled_and_grey() {
+        let image = checkerboard().scaled(2);
+        assert_eq!((image.width(), image.height()), (6, 4));
+        assert_eq!(image.get(1, 1), Rgb::WHITE);
+        assert_eq!(image.get(2, 1), Rgb(255, 0, 0));
+        assert_eq!(Rgb::grey(5, 10), Rgb(127, 127, 127));
+        assert_eq!(Rgb::grey(3, 0), Rgb::BLACK);
+    }
+}
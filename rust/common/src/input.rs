@@ -0,0 +1,66 @@
+use crate::{workspace_dir, Day};
+use anyhow::{anyhow, Context};
+use std::{
+    io::{stdin, IsTerminal, Read},
+    path::{Path, PathBuf},
+};
+
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct InputArgs {
+    /// Read the puzzle input from this file instead of stdin or the day's `input.txt`
+    #[arg(long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+}
+
+pub fn read_stdin() -> anyhow::Result<String> {
+    let mut s = String::new();
+    stdin().read_to_string(&mut s)?;
+    Ok(s)
+}
+
+fn read_file(path: &Path) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+}
+
+impl Day {
+    /// The directory holding the day's source file, fixtures and cached `input.txt`.
+    pub fn source_dir(&self) -> PathBuf {
+        // `file!()` is relative to the workspace root for workspace members
+        let workspace = workspace_dir();
+        let source = workspace.join(self.source);
+        source.parent().unwrap_or(workspace).to_owned()
+    }
+
+    pub fn input_path(&self) -> PathBuf {
+        self.source_dir().join("input.txt")
+    }
+
+    /// Reads the puzzle input from `args.input` if given, otherwise from stdin when something is
+    /// piped to it, and finally from the `input.txt` next to the day's source.
+    pub fn read_input(&self, args: &InputArgs) -> anyhow::Result<String> {
+        if let Some(path) = &args.input {
+            return read_file(path);
+        }
+
+        if !stdin().is_terminal() {
+            let s = read_stdin()?;
+            if !s.trim().is_empty() {
+                return Ok(s);
+            }
+        }
+
+        let path = self.input_path();
+        if !path.exists() {
+            return Err(anyhow!(
+                "no input for {} day {}: pipe it to stdin, pass --input <PATH>, save it as {} \
+                 or download it with `aoc fetch {} {}`",
+                self.year,
+                self.day,
+                path.display(),
+                self.year,
+                self.day
+            ));
+        }
+        read_file(&path)
+    }
+}
@@ -0,0 +1,215 @@
+use clap::Parser;
+
+mod answers;
+mod bench;
+pub mod image;
+mod input;
+mod output;
+pub mod parallel;
+pub mod params;
+mod parse;
+pub mod search;
+pub mod visualize;
+
+pub use answers::{Check, Manifest, Outcome};
+pub use bench::{append_report, bench, BenchOptions, Measurement, Stats};
+pub use image::ImageArgs;
+pub use input::{read_stdin, InputArgs};
+pub use output::{run, Answer, Format, OutputArgs};
+pub use parallel::ParallelArgs;
+pub use params::{Param, ParamArgs};
+pub use parse::{locate_error, parse_lines, ParseError};
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+pub use visualize::VisualizeArgs;
+
+/// A solution for one part of a puzzle, with the answer rendered as text.
+pub type Part = fn(&str) -> anyhow::Result<String>;
+
+/// The root of the Cargo workspace holding all the years.
+pub fn workspace_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+/// The recorded correct answers for every year, see [`Manifest`].
+pub fn answers_path() -> PathBuf {
+    workspace_dir().join("answers.txt")
+}
+
+/// The default location of the report written by `aoc bench`.
+pub fn bench_report_path() -> PathBuf {
+    workspace_dir().join("bench.tsv")
+}
+
+/// The solution for a single day. Days export one of these as `DAY`, built with [`day!`].
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub year: u32,
+    pub day: u32,
+    /// Path of the day's source file, as given by `file!()`
+    pub source: &'static str,
+    pub part_1: Part,
+    pub part_2: Option<Part>,
+    /// Just the parsing of the input, for testing the parser on its own. Days whose solutions
+    /// take any text as is don't have one.
+    pub parse: Option<ParseFn>,
+    /// The puzzle constants that can be changed from the command line, see [`params`]
+    pub params: &'static [Param],
+}
+
+/// Parses the puzzle input, throwing the result away.
+pub type ParseFn = fn(&str) -> anyhow::Result<()>;
+
+impl Day {
+    pub fn part(&self, part: u8) -> Option<Part> {
+        match part {
+            1 => Some(self.part_1),
+            2 => self.part_2,
+            _ => None,
+        }
+    }
+}
+
+/// Builds a [`Day`] from the `part_1` (and `part_2`) functions in scope. The functions take the
+/// puzzle input and return an `anyhow::Result` of anything that implements `Display`.
+///
+/// The day's input parser is given with `parse:`, as anything callable with the input that returns
+/// a `Result`, and its tunable constants with `params:`:
+///
+/// ```ignore
+/// pub const DAY: aoc_common::Day = aoc_common::day!(2024, 6, parse: Area::from_str);
+/// pub const DAY: aoc_common::Day = aoc_common::day!(2019, 10, part_1 only, parse: parse);
+/// pub const DAY: aoc_common::Day =
+///     aoc_common::day!(2022, 6, params: [PACKET_MARKER, MESSAGE_MARKER]);
+/// ```
+#[macro_export]
+macro_rules! day {
+    (
+        $year:literal, $day:literal
+        $(, parse: $parse:expr)?
+        $(, params: [$($param:expr),* $(,)?])?
+    ) => {
+        $crate::day!(@build $year, $day, Some(|input| {
+            let answer = part_2(input).map_err(|e| $crate::locate_error(e, $year, $day))?;
+            Ok(answer.to_string())
+        }), [$($parse)?], [$($($param),*)?])
+    };
+    (
+        $year:literal, $day:literal, part_1 only
+        $(, parse: $parse:expr)?
+        $(, params: [$($param:expr),* $(,)?])?
+    ) => {
+        $crate::day!(@build $year, $day, None, [$($parse)?], [$($($param),*)?])
+    };
+    (
+        @build $year:literal, $day:literal, $part_2:expr,
+        [$($parse:expr)?], [$($param:expr),*]
+    ) => {
+        $crate::Day {
+            year: $year,
+            day: $day,
+            source: file!(),
+            part_1: |input| {
+                let answer = part_1(input).map_err(|e| $crate::locate_error(e, $year, $day))?;
+                Ok(answer.to_string())
+            },
+            part_2: $part_2,
+            parse: $crate::day!(@parse $year, $day, [$($parse)?]),
+            params: &[$($param),*],
+        }
+    };
+    (@parse $year:literal, $day:literal, []) => {
+        None
+    };
+    (@parse $year:literal, $day:literal, [$parse:expr]) => {
+        Some(|input| {
+            $parse(input).map_err(|e| $crate::locate_error(e.into(), $year, $day))?;
+            Ok(())
+        })
+    };
+}
+
+/// Tests the `part_1`/`part_2` functions in scope against the puzzle's worked examples. Each
+/// example is an input file next to the day's source, listed with the expected answer of every part
+/// it covers:
+///
+/// ```ignore
+/// aoc_common::examples! {
+///     "example.txt" => { part_1: 24000, part_2: 45000 },
+///     "example_2.txt" => { part_2: 281 },
+/// }
+/// ```
+///
+/// Answers are compared as text, so numbers and strings can be mixed freely.
+#[macro_export]
+macro_rules! examples {
+    ($($file:literal => { $($part:ident: $expected:expr),+ $(,)? }),+ $(,)?) => {
+        #[cfg(test)]
+        mod examples {
+            use super::*;
+
+            #[test]
+            fn test_examples() {
+                $($(
+                    let answer = $part(include_str!($file))
+                        .unwrap_or_else(|err| panic!("{} of {}: {err:#}", stringify!($part), $file));
+                    assert_eq!(
+                        answer.to_string(),
+                        $expected.to_string(),
+                        "{} of {}",
+                        stringify!($part),
+                        $file
+                    );
+                )+)+
+            }
+        }
+    };
+}
+
+#[derive(Parser)]
+struct DayCli {
+    #[command(flatten)]
+    input: InputArgs,
+    #[command(flatten)]
+    output: OutputArgs,
+    #[command(flatten)]
+    visualize: VisualizeArgs,
+    #[command(flatten)]
+    image: ImageArgs,
+    #[command(flatten)]
+    parallel: ParallelArgs,
+    #[command(flatten)]
+    params: ParamArgs,
+}
+
+/// Entry point for the per-day binaries: solves `day` for the puzzle input given on the command
+/// line, see [`Day::read_input`].
+pub fn main(day: &Day) -> ExitCode {
+    let cli = DayCli::parse();
+    cli.visualize.install();
+    cli.image.install();
+    cli.parallel.install();
+    if cli.params.list_params {
+        print!("{}", params::describe(day));
+        return ExitCode::SUCCESS;
+    }
+    report(cli.params.install(day).and_then(|()| {
+        let input = day.read_input(&cli.input)?;
+        run(day, &input, None, cli.output.format)
+    }))
+}
+
+/// Turns the outcome of a binary's work into its exit code, printing any error with its causes.
+/// Unlike returning the error from `main`, this shows a [`ParseError`]'s diagnostic as is,
+/// without a backtrace.
+pub fn report(result: anyhow::Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::Day;
+use anyhow::anyhow;
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+/// How solutions print their answers.
+#[derive(clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `part 1: <answer>`, for people
+    #[default]
+    Text,
+    /// One JSON object per answer, one answer per line
+    Json,
+    /// Tab separated values with a header row. Newlines and tabs in answers are written as `\n`
+    /// and `\t`.
+    Tsv,
+}
+
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct OutputArgs {
+    /// How to print the answers
+    #[arg(long, value_enum, default_value_t)]
+    pub format: Format,
+}
+
+/// The answer to one part of a day, and how long it took to find.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+impl Day {
+    /// Solves `part` for `input`, timing the solution.
+    pub fn solve(&self, part: u8, input: &str) -> anyhow::Result<Answer> {
+        let solve = self
+            .part(part)
+            .ok_or_else(|| anyhow!("{} day {} has no part {part}", self.year, self.day))?;
+        let start = Instant::now();
+        let answer = solve(input)?;
+        Ok(Answer {
+            year: self.year,
+            day: self.day,
+            part,
+            answer,
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+const TSV_HEADER: &str = "year\tday\tpart\tanswer\telapsed_ns";
+
+impl Format {
+    /// The line to print before any answers, if the format has one.
+    pub fn header(self) -> Option<&'static str> {
+        match self {
+            Format::Tsv => Some(TSV_HEADER),
+            Format::Text | Format::Json => None,
+        }
+    }
+
+    /// Renders `answer` as it's printed, without a trailing newline.
+    pub fn format(self, answer: &Answer) -> String {
+        let Answer {
+            year,
+            day,
+            part,
+            answer,
+            elapsed,
+        } = answer;
+        let elapsed = elapsed.as_nanos();
+        match self {
+            // Multi-line answers (like a rendered CRT) go on their own lines
+            Format::Text if answer.contains('\n') => format!("part {part}:\n{answer}"),
+            Format::Text => format!("part {part}: {answer}"),
+            Format::Json => format!(
+                r#"{{"year":{year},"day":{day},"part":{part},"answer":{},"elapsed_ns":{elapsed}}}"#,
+                json_string(answer)
+            ),
+            Format::Tsv => format!(
+                "{year}\t{day}\t{part}\t{}\t{elapsed}",
+                answer
+                    .replace('\\', "\\\\")
+                    .replace('\n', "\\n")
+                    .replace('\t', "\\t")
+            ),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Solves `part` of `day`, or every part when `None`, printing each answer as it comes in.
+pub fn run(day: &Day, input: &str, part: Option<u8>, format: Format) -> anyhow::Result<()> {
+    let parts = match part {
+        Some(part) => vec![part],
+        None => [1, 2]
+            .into_iter()
+            .filter(|p| day.part(*p).is_some())
+            .collect(),
+    };
+
+    if let Some(header) = format.header() {
+        println!("{header}");
+    }
+    for part in parts {
+        println!("{}", format.format(&day.solve(part, input)?));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(answer: &str) -> Answer {
+        Answer {
+            year: 2022,
+            day: 10,
+            part: 2,
+            answer: answer.to_owned(),
+            elapsed: Duration::from_micros(1500),
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(Format::Text.format(&answer("42")), "part 2: 42");
+        assert_eq!(Format::Text.format(&answer("#.\n.#")), "part 2:\n#.\n.#");
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            Format::Json.format(&answer("#.\n\"x\"\\")),
+            r##"{"year":2022,"day":10,"part":2,"answer":"#.\n\"x\"\\","elapsed_ns":1500000}"##
+        );
+        assert_eq!(json_string("\u{1}"), r#""\u0001""#);
+    }
+
+    #[test]
+    fn test_tsv() {
+        assert_eq!(
+            Format::Tsv.format(&answer("#.\n.#\tx")),
+            "2022\t10\t2\t#.\\n.#\\tx\t1500000"
+        );
+        assert_eq!(Format::Tsv.header().unwrap().split('\t').count(), 5);
+    }
+}
@@ -0,0 +1,184 @@
+//! Spreading brute-force searches over several threads.
+//!
+//! Searches run serially unless the binary was started with `--parallel`. Either way they give
+//! the same answers: [`find_first`] always finds the smallest match, not the first one a thread
+//! happens to stumble on.
+//!
+//! The candidates are handed out to the workers in chunks, in increasing order, from a shared
+//! counter. Once a match is found, workers stop taking chunks that start after it, so a search
+//! that finds something early doesn't run through the rest of the space.
+
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct ParallelArgs {
+    /// Spread brute-force searches over N threads, or over every core when N is left out
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "0")]
+    pub parallel: Option<usize>,
+}
+
+impl ParallelArgs {
+    /// Turns on parallel searches for the rest of the process if `--parallel` was given.
+    pub fn install(&self) {
+        if let Some(threads) = self.parallel {
+            let threads = match threads {
+                0 => thread::available_parallelism().map_or(1, |n| n.get()),
+                n => n,
+            };
+            *THREADS.lock().unwrap() = threads;
+        }
+    }
+}
+
+static THREADS: Mutex<usize> = Mutex::new(1);
+
+/// How many threads searches are spread over, 1 when running serially.
+pub fn threads() -> usize {
+    *THREADS.lock().unwrap()
+}
+
+/// Below this many candidates starting the threads costs more than it saves.
+const MIN_PARALLEL: usize = 1024;
+/// The most candidates a worker takes at once, which bounds how far past a match it searches.
+const MAX_CHUNK: usize = 4096;
+
+/// The smallest number in `range` matching `f`.
+pub fn find_first<F>(range: Range<usize>, f: F) -> Option<usize>
+where
+    F: Fn(usize) -> bool + Sync,
+{
+    find_first_on(threads(), range, f)
+}
+
+/// Whether any number in `range` matches `f`.
+pub fn any<F>(range: Range<usize>, f: F) -> bool
+where
+    F: Fn(usize) -> bool + Sync,
+{
+    find_first(range, f).is_some()
+}
+
+/// How many of `items` match `f`.
+pub fn count<T, F>(items: &[T], f: F) -> usize
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    count_on(threads(), items, f)
+}
+
+fn chunk_size(threads: usize, len: usize) -> usize {
+    (len / (threads * 8)).clamp(1, MAX_CHUNK)
+}
+
+fn find_first_on<F>(threads: usize, range: Range<usize>, f: F) -> Option<usize>
+where
+    F: Fn(usize) -> bool + Sync,
+{
+    if threads <= 1 || range.len() < MIN_PARALLEL {
+        return range.into_iter().find(|n| f(*n));
+    }
+
+    let chunk = chunk_size(threads, range.len());
+    let next = AtomicUsize::new(range.start);
+    let found = AtomicUsize::new(usize::MAX);
+    // Stops handing out chunks at the end of the range rather than let the counter wrap around
+    // to the start of the numbers
+    let take_chunk = || {
+        next.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+            (n < range.end).then(|| n.saturating_add(chunk).min(range.end))
+        })
+    };
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                while let Ok(start) = take_chunk() {
+                    // Every chunk before a match has been handed out already, so nothing smaller
+                    // than the match can be left in the chunks after it
+                    if start >= found.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let end = start.saturating_add(chunk).min(range.end);
+                    if let Some(n) = (start..end).find(|n| f(*n)) {
+                        found.fetch_min(n, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    match found.into_inner() {
+        usize::MAX => None,
+        n => Some(n),
+    }
+}
+
+fn count_on<T, F>(threads: usize, items: &[T], f: F) -> usize
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    if threads <= 1 || items.len() < 2 {
+        return items.iter().filter(|item| f(item)).count();
+    }
+
+    let chunk = chunk_size(threads, items.len());
+    let next = AtomicUsize::new(0);
+    let total = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| loop {
+                let start = next.fetch_add(chunk, Ordering::Relaxed);
+                let Some(items) = items.get(start..(start + chunk).min(items.len())) else {
+                    break;
+                };
+                let matches = items.iter().filter(|item| f(item)).count();
+                total.fetch_add(matches, Ordering::Relaxed);
+            });
+        }
+    });
+    total.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_first() {
+        let is_match = |n: usize| n % 7919 == 7918 || n == 100_003;
+        for threads in [1, 2, 3, 8] {
+            assert_eq!(find_first_on(threads, 0..200_000, is_match), Some(7918));
+            assert_eq!(find_first_on(threads, 8000..200_000, is_match), Some(15837));
+            assert_eq!(find_first_on(threads, 0..7918, is_match), None);
+            assert_eq!(
+                find_first_on(threads, 0..usize::MAX, |n| n == 50_000),
+                Some(50_000)
+            );
+            // Running off the end of the numbers mustn't wrap around to the small ones
+            let end = usize::MAX - 10_000..usize::MAX;
+            assert_eq!(find_first_on(threads, end.clone(), |n| n < 10_000), None);
+            assert_eq!(
+                find_first_on(threads, end, |n| n == usize::MAX - 1),
+                Some(usize::MAX - 1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_count() {
+        let items: Vec<u32> = (0..10_000).collect();
+        for threads in [1, 2, 3, 8] {
+            assert_eq!(count_on(threads, &items, |n| n % 3 == 0), 3334);
+            assert_eq!(count_on(threads, &items[..1], |n| n % 3 == 0), 1);
+            assert_eq!(count_on(threads, &[] as &[u32], |_| true), 0);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Puzzle constants that can be changed from the command line.
+//!
+//! A day declares its tunable constants as [`Param`]s, with the puzzle's values as defaults, and
+//! lists them in [`crate::day!`] with `params: [...]`. Solutions read them with [`Param::value`]
+//! or [`Param::list`], which give the default unless the binary was started with
+//! `--param NAME=VALUE`. That makes it possible to try variants of a puzzle, or the different
+//! constants some worked examples use, without editing the code.
+
+use crate::Day;
+use anyhow::{anyhow, bail, Context};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr, sync::Mutex};
+
+/// A constant of a puzzle, with its value as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    /// The name to override it with, in kebab-case
+    pub name: &'static str,
+    pub default: &'static str,
+    pub help: &'static str,
+}
+
+static OVERRIDES: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+impl Param {
+    /// The value given on the command line, or the default.
+    pub fn text(&self) -> String {
+        let overrides = OVERRIDES.lock().unwrap();
+        overrides
+            .get(self.name)
+            .cloned()
+            .unwrap_or_else(|| self.default.to_owned())
+    }
+
+    pub fn value<T>(&self) -> anyhow::Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let text = self.text();
+        text.trim()
+            .parse()
+            .map_err(|err| anyhow!("parameter {}: {err}: {text:?}", self.name))
+    }
+
+    /// The value as a comma separated list.
+    pub fn list<T>(&self) -> anyhow::Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let text = self.text();
+        text.split(',')
+            .map(|item| item.trim().parse())
+            .collect::<Result<_, _>>()
+            .map_err(|err| anyhow!("parameter {}: {err}: {text:?}", self.name))
+    }
+}
+
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct ParamArgs {
+    /// Change one of the puzzle's constants, see --list-params. Can be given more than once.
+    #[arg(long = "param", value_name = "NAME=VALUE")]
+    pub params: Vec<String>,
+    /// List the puzzle's constants that --param can change, with their defaults, and exit
+    #[arg(long)]
+    pub list_params: bool,
+}
+
+impl ParamArgs {
+    /// Makes the `--param` overrides visible to `day`'s solutions for the rest of the process.
+    /// Names `day` doesn't declare are an error.
+    pub fn install(&self, day: &Day) -> anyhow::Result<()> {
+        let mut overrides = BTreeMap::new();
+        for param in &self.params {
+            let (name, value) = param
+                .split_once('=')
+                .with_context(|| format!("expected NAME=VALUE, got {param:?}"))?;
+            if !day.params.iter().any(|p| p.name == name) {
+                let known: Vec<_> = day.params.iter().map(|p| p.name).collect();
+                bail!(
+                    "{} day {} has no parameter {name:?}, it has: {}",
+                    day.year,
+                    day.day,
+                    if known.is_empty() {
+                        "none".to_owned()
+                    } else {
+                        known.join(", ")
+                    }
+                );
+            }
+            overrides.insert(name.to_owned(), value.to_owned());
+        }
+        *OVERRIDES.lock().unwrap() = overrides;
+        Ok(())
+    }
+}
+
+/// Lists `day`'s parameters with their defaults and what they do, one per line.
+pub fn describe(day: &Day) -> String {
+    if day.params.is_empty() {
+        return format!("{} day {} has no parameters\n", day.year, day.day);
+    }
+    let width = day.params.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    day.params
+        .iter()
+        .map(|p| format!("{:width$}  {} (default {})\n", p.name, p.help, p.default))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROUNDS: Param = Param {
+        name: "test-rounds",
+        default: "20",
+        help: "How many rounds to play",
+    };
+    const CYCLES: Param = Param {
+        name: "test-cycles",
+        default: "20, 60,100",
+        help: "The cycles to sample",
+    };
+
+    fn day() -> Day {
+        Day {
+            year: 2022,
+            day: 99,
+            source: file!(),
+            part_1: |_| Ok(String::new()),
+            part_2: None,
+            parse: None,
+            params: &[ROUNDS, CYCLES],
+        }
+    }
+
+    fn args(params: &[&str]) -> ParamArgs {
+        ParamArgs {
+            params: params.iter().map(|s| s.to_string()).collect(),
+            list_params: false,
+        }
+    }
+
+    // The overrides are global, so this is the only test that installs any
+    #[test]
+    fn test_overrides() {
+        assert_eq!(ROUNDS.value::<usize>().unwrap(), 20);
+        assert_eq!(CYCLES.list::<usize>().unwrap(), [20, 60, 100]);
+
+        args(&["test-rounds=5", "test-cycles=1,2"])
+            .install(&day())
+            .unwrap();
+        assert_eq!(ROUNDS.value::<usize>().unwrap(), 5);
+        assert_eq!(CYCLES.list::<usize>().unwrap(), [1, 2]);
+
+        args(&["test-rounds=lots"]).install(&day()).unwrap();
+        let err = ROUNDS.value::<usize>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parameter test-rounds: invalid digit found in string: \"lots\""
+        );
+
+        let err = args(&["rounds=5"]).install(&day()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "2022 day 99 has no parameter \"rounds\", it has: test-rounds, test-cycles"
+        );
+        assert!(args(&["test-rounds"]).install(&day()).is_err());
+
+        args(&[]).install(&day()).unwrap();
+        assert_eq!(ROUNDS.value::<usize>().unwrap(), 20);
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe(&day()),
+            "test-rounds  How many rounds to play (default 20)\n\
+             test-cycles  The cycles to sample (default 20, 60,100)\n"
+        );
+    }
+}
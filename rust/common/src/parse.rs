@@ -0,0 +1,157 @@
+use std::fmt::Display;
+
+/// A malformed puzzle input, pointing at where in the input things went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The year and day of the puzzle, filled in when the error passes through a [`crate::Day`]
+    pub day: Option<(u32, u32)>,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+    /// The offending line
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error at byte `offset` into `input`. Offsets past the end point just after the last
+    /// character.
+    pub fn at_offset(input: &str, offset: usize, message: impl Display) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line_start = input[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |idx| offset + idx);
+        Self {
+            day: None,
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            text: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_owned(),
+            message: message.to_string(),
+        }
+    }
+
+    /// An error at the start of `fragment`, which must be a slice of `input` like the ones
+    /// `str::lines`, `str::split` or a nom parser hand out.
+    pub fn at(input: &str, fragment: &str, message: impl Display) -> Self {
+        let offset = (fragment.as_ptr() as usize).saturating_sub(input.as_ptr() as usize);
+        Self::at_offset(input, offset, message)
+    }
+
+    /// An error at the start of the 0-based line `idx` of `input`.
+    pub fn on_line(input: &str, idx: usize, message: impl Display) -> Self {
+        Self::at_column(input, idx, 0, message)
+    }
+
+    /// An error at the 0-based character `column` of the 0-based line `line` of `input`.
+    pub fn at_column(input: &str, line: usize, column: usize, message: impl Display) -> Self {
+        let line_start = input
+            .split_inclusive('\n')
+            .take(line)
+            .map(str::len)
+            .sum::<usize>();
+        let offset = input[line_start..]
+            .char_indices()
+            .take_while(|(_, c)| *c != '\n')
+            .map(|(idx, c)| line_start + idx + c.len_utf8())
+            .take(column)
+            .last()
+            .unwrap_or(line_start);
+        Self::at_offset(input, offset, message)
+    }
+
+    /// An error for an input that ends before the parser found what it needed.
+    pub fn at_end(input: &str, message: impl Display) -> Self {
+        Self::at_offset(input, input.trim_end().len(), message)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((year, day)) = self.day {
+            write!(f, "{year} day {day}, ")?;
+        }
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(f, "{gutter} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses every line of `input` with `f`, reporting failures as a [`ParseError`] on that line.
+pub fn parse_lines<T, E, F>(input: &str, mut f: F) -> Result<Vec<T>, ParseError>
+where
+    E: Display,
+    F: FnMut(&str) -> Result<T, E>,
+{
+    input
+        .lines()
+        .map(|line| f(line).map_err(|err| ParseError::at(input, line, err)))
+        .collect()
+}
+
+/// Fills in the year and day of a [`ParseError`] anywhere in `err`'s chain of causes.
+pub fn locate_error(mut err: anyhow::Error, year: u32, day: u32) -> anyhow::Error {
+    if let Some(parse_error) = err.downcast_mut::<ParseError>() {
+        parse_error.day.get_or_insert((year, day));
+    }
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at() {
+        let input = "move 1 from 2 to 1\nmove 3 from x to 3\n";
+        let x = &input[input.find('x').unwrap()..];
+        let err = ParseError::at(input, x, "expected a number");
+        assert_eq!((err.line, err.column), (2, 13));
+        assert_eq!(err.text, "move 3 from x to 3");
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 13: expected a number\n  |\n2 | move 3 from x to 3\n  |             ^"
+        );
+    }
+
+    #[test]
+    fn test_on_line_and_at_end() {
+        let input = "a\nbb\nccc\n";
+        assert_eq!(ParseError::on_line(input, 2, "").text, "ccc");
+        let err = ParseError::at_column(input, 2, 2, "");
+        assert_eq!((err.line, err.column), (3, 3));
+        let err = ParseError::at_end(input, "unexpected end of input");
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 4, "ccc"));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let input = "1\n2\nthree\n";
+        assert_eq!(parse_lines("1\n2\n", str::parse::<u32>), Ok(vec![1, 2]));
+        let err = parse_lines(input, str::parse::<u32>).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.message, "invalid digit found in string");
+    }
+
+    #[test]
+    fn test_locate_error() {
+        let err = anyhow::Error::new(ParseError::at_offset("x", 0, "bad"));
+        let err = locate_error(err.context("parsing input"), 2022, 5);
+        let parse_error = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(parse_error.day, Some((2022, 5)));
+    }
+}
@@ -0,0 +1,316 @@
+//! Shortest path searches over implicit graphs, where the graph is given by a `successors`
+//! function and the search stops at the first node matching `is_goal`.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// Breadth-first search from `start`, returning the path including both ends.
+pub fn bfs<N, FS, S, FG>(start: N, successors: FS, is_goal: FG) -> Option<Vec<N>>
+where
+    N: Hash + Eq + Clone,
+    FS: FnMut(&N) -> S,
+    S: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    bfs_multi([start], successors, is_goal)
+}
+
+/// Breadth-first search from all of `starts` at once, returning the shortest path from whichever
+/// start is closest to a goal.
+pub fn bfs_multi<N, I, FS, S, FG>(starts: I, mut successors: FS, mut is_goal: FG) -> Option<Vec<N>>
+where
+    N: Hash + Eq + Clone,
+    I: IntoIterator<Item = N>,
+    FS: FnMut(&N) -> S,
+    S: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut nodes = Nodes::default();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let (idx, true) = nodes.insert(start, 0, None) {
+            queue.push_back(idx);
+        }
+    }
+
+    while let Some(idx) = queue.pop_front() {
+        if is_goal(&nodes.keys[idx]) {
+            return Some(nodes.path(idx));
+        }
+        let cost = nodes.costs[idx] + 1;
+        for next in successors(&nodes.keys[idx]) {
+            if let (next, true) = nodes.insert(next, cost, Some(idx)) {
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm with weighted edges: `successors` yields `(node, edge_cost)` pairs.
+/// Returns the cheapest path and its cost.
+pub fn dijkstra<N, FS, S, FG>(start: N, successors: FS, is_goal: FG) -> Option<(Vec<N>, u32)>
+where
+    N: Hash + Eq + Clone,
+    FS: FnMut(&N) -> S,
+    S: IntoIterator<Item = (N, u32)>,
+    FG: FnMut(&N) -> bool,
+{
+    a_star(start, successors, |_| 0, is_goal)
+}
+
+/// Like [`dijkstra`], but returns every path of the lowest cost.
+pub fn dijkstra_all<N, FS, S, FG>(
+    start: N,
+    successors: FS,
+    is_goal: FG,
+) -> Option<(Vec<Vec<N>>, u32)>
+where
+    N: Hash + Eq + Clone,
+    FS: FnMut(&N) -> S,
+    S: IntoIterator<Item = (N, u32)>,
+    FG: FnMut(&N) -> bool,
+{
+    a_star_all(start, successors, |_| 0, is_goal)
+}
+
+/// A* search with weighted edges. The `heuristic` must never overestimate the remaining cost to a
+/// goal, or the returned path may not be the cheapest. It need not be consistent: a node is
+/// expanded again whenever a cheaper way to it turns up.
+pub fn a_star<N, FS, S, FH, FG>(
+    start: N,
+    successors: FS,
+    heuristic: FH,
+    is_goal: FG,
+) -> Option<(Vec<N>, u32)>
+where
+    N: Hash + Eq + Clone,
+    FS: FnMut(&N) -> S,
+    S: IntoIterator<Item = (N, u32)>,
+    FH: FnMut(&N) -> u32,
+    FG: FnMut(&N) -> bool,
+{
+    let (nodes, goals, cost) = search([start], successors, heuristic, is_goal, false)?;
+    Some((nodes.path(goals[0]), cost))
+}
+
+/// Like [`a_star`], but returns every path of the lowest cost. This needs a consistent heuristic,
+/// i.e. one that never drops by more than the edge cost between two neighbours.
+pub fn a_star_all<N, FS, S, FH, FG>(
+    start: N,
+    successors: FS,
+    heuristic: FH,
+    is_goal: FG,
+) -> Option<(Vec<Vec<N>>, u32)>
+where
+    N: Hash + Eq + Clone,
+    FS: FnMut(&N) -> S,
+    S: IntoIterator<Item = (N, u32)>,
+    FH: FnMut(&N) -> u32,
+    FG: FnMut(&N) -> bool,
+{
+    let (nodes, goals, cost) = search([start], successors, heuristic, is_goal, true)?;
+    let paths = goals.into_iter().flat_map(|goal| nodes.paths(goal));
+    Some((paths.collect(), cost))
+}
+
+/// Every node seen by a search, addressed by the index it was first seen at.
+struct Nodes<N> {
+    index: HashMap<N, usize>,
+    keys: Vec<N>,
+    costs: Vec<u32>,
+    /// The predecessors on the cheapest known paths to each node
+    parents: Vec<Vec<usize>>,
+}
+
+impl<N> Default for Nodes<N> {
+    fn default() -> Self {
+        Self {
+            index: HashMap::new(),
+            keys: Vec::new(),
+            costs: Vec::new(),
+            parents: Vec::new(),
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> Nodes<N> {
+    /// Records that `node` can be reached for `cost` via `parent`. Returns the node's index and
+    /// whether `cost` is cheaper than what was known before.
+    fn insert(&mut self, node: N, cost: u32, parent: Option<usize>) -> (usize, bool) {
+        let parent = parent.into_iter().collect();
+        match self.index.get(&node) {
+            None => {
+                let idx = self.keys.len();
+                self.index.insert(node.clone(), idx);
+                self.keys.push(node);
+                self.costs.push(cost);
+                self.parents.push(parent);
+                (idx, true)
+            }
+            Some(&idx) if cost < self.costs[idx] => {
+                self.costs[idx] = cost;
+                self.parents[idx] = parent;
+                (idx, true)
+            }
+            Some(&idx) => {
+                if cost == self.costs[idx] {
+                    self.parents[idx].extend(parent);
+                }
+                (idx, false)
+            }
+        }
+    }
+
+    /// One of the cheapest paths from a start to `idx`.
+    fn path(&self, mut idx: usize) -> Vec<N> {
+        let mut path = vec![self.keys[idx].clone()];
+        while let Some(parent) = self.parents[idx].first() {
+            idx = *parent;
+            path.push(self.keys[idx].clone());
+        }
+        path.reverse();
+        path
+    }
+
+    /// Every cheapest path from a start to `idx`.
+    fn paths(&self, idx: usize) -> Vec<Vec<N>> {
+        if self.parents[idx].is_empty() {
+            return vec![vec![self.keys[idx].clone()]];
+        }
+        self.parents[idx]
+            .iter()
+            .flat_map(|parent| self.paths(*parent))
+            .map(|mut path| {
+                path.push(self.keys[idx].clone());
+                path
+            })
+            .collect()
+    }
+}
+
+/// The weighted search behind [`a_star`] and friends. Returns the goals reached at the lowest
+/// cost; with `all` set it keeps going until every goal and path of that cost has been found.
+fn search<N, I, FS, S, FH, FG>(
+    starts: I,
+    mut successors: FS,
+    mut heuristic: FH,
+    mut is_goal: FG,
+    all: bool,
+) -> Option<(Nodes<N>, Vec<usize>, u32)>
+where
+    N: Hash + Eq + Clone,
+    I: IntoIterator<Item = N>,
+    FS: FnMut(&N) -> S,
+    S: IntoIterator<Item = (N, u32)>,
+    FH: FnMut(&N) -> u32,
+    FG: FnMut(&N) -> bool,
+{
+    let mut nodes = Nodes::default();
+    let mut open = BinaryHeap::new();
+    for start in starts {
+        let h = heuristic(&start);
+        if let (idx, true) = nodes.insert(start, 0, None) {
+            open.push(Reverse((h, 0, idx)));
+        }
+    }
+
+    let mut goals = vec![];
+    let mut best = None;
+
+    while let Some(Reverse((estimate, cost, idx))) = open.pop() {
+        if best.is_some_and(|best| estimate > best) {
+            break;
+        }
+        // Each node is queued once per improvement to its cost, so only the latest entry counts
+        if cost > nodes.costs[idx] {
+            continue;
+        }
+
+        if is_goal(&nodes.keys[idx]) {
+            best = Some(cost);
+            goals.push(idx);
+            if !all {
+                break;
+            }
+            continue;
+        }
+
+        for (next, edge_cost) in successors(&nodes.keys[idx]) {
+            let next_cost = cost + edge_cost;
+            let h = heuristic(&next);
+            if let (next, true) = nodes.insert(next, next_cost, Some(idx)) {
+                open.push(Reverse((next_cost + h, next_cost, next)));
+            }
+        }
+    }
+
+    best.map(|cost| (nodes, goals, cost))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Moves along a line of numbers, either one step for 1 or two steps for 3.
+    fn line_successors(n: &i32) -> [(i32, u32); 2] {
+        [(n + 1, 1), (n + 2, 3)]
+    }
+
+    #[test]
+    fn test_bfs() {
+        let path = bfs(0, |n| [n + 1, n * 2], |n| *n == 10);
+        assert_eq!(path, Some(vec![0, 1, 2, 4, 5, 10]));
+        assert_eq!(bfs(0, |n| (*n < 10).then_some(n + 2), |n| *n == 3), None);
+    }
+
+    #[test]
+    fn test_bfs_multi() {
+        let path = bfs_multi([0, 7], |n| [n + 1], |n| *n == 9);
+        assert_eq!(path, Some(vec![7, 8, 9]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let (path, cost) = dijkstra(0, line_successors, |n| *n == 4).unwrap();
+        assert_eq!(path, vec![0, 1, 2, 3, 4]);
+        assert_eq!(cost, 4);
+    }
+
+    #[test]
+    fn test_a_star_inconsistent_heuristic() {
+        // The heuristic is admissible but overestimates the drop from B to A, so A is first
+        // expanded via the dearer direct edge and has to be reopened once B finds a cheaper way.
+        let successors = |n: &char| match n {
+            'S' => vec![('A', 4), ('B', 1)],
+            'B' => vec![('A', 1)],
+            'A' => vec![('G', 5)],
+            _ => vec![],
+        };
+        let h = |n: &char| if *n == 'B' { 6 } else { 0 };
+        let (path, cost) = a_star('S', successors, h, |n| *n == 'G').unwrap();
+        assert_eq!(path, vec!['S', 'B', 'A', 'G']);
+        assert_eq!(cost, 7);
+    }
+
+    #[test]
+    fn test_a_star_all() {
+        // Two equally cheap ways around a square from (0, 0) to (1, 1)
+        let successors = |&(x, y): &(i32, i32)| {
+            [((x + 1, y), 1), ((x, y + 1), 1)]
+                .into_iter()
+                .filter(|((x, y), _)| *x <= 1 && *y <= 1)
+        };
+        let h = |(x, y): &(i32, i32)| (1 - x + 1 - y) as u32;
+        let (mut paths, cost) = a_star_all((0, 0), successors, h, |p| *p == (1, 1)).unwrap();
+        paths.sort();
+        assert_eq!(cost, 2);
+        assert_eq!(
+            paths,
+            vec![vec![(0, 0), (0, 1), (1, 1)], vec![(0, 0), (1, 0), (1, 1)]]
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Terminal animation for the puzzles that simulate step by step.
+//!
+//! A simulation calls [`frame`] once per step with a closure that draws the current state.
+//! Nothing is drawn, and the closure is never called, unless the binary was started with
+//! `--visualize`, so the hooks cost next to nothing when solving normally. Frames go to stderr to
+//! keep stdout for the answers.
+
+use std::{
+    fs::File,
+    io::{stderr, stdin, BufRead, BufReader, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct VisualizeArgs {
+    /// Animate simulations in the terminal while solving
+    #[arg(long)]
+    pub visualize: bool,
+    /// Pause this long between frames
+    #[arg(long, value_name = "MS", default_value_t = 50)]
+    pub frame_delay: u64,
+    /// Wait for Enter after every frame instead of pausing. Enter `c` to continue without
+    /// stepping, or `q` to stop drawing.
+    #[arg(long)]
+    pub step: bool,
+}
+
+impl VisualizeArgs {
+    /// Turns on drawing for the rest of the process if `--visualize` or `--step` was given.
+    pub fn install(&self) {
+        if self.visualize || self.step {
+            let visualizer = Visualizer::new(
+                Box::new(stderr()),
+                Duration::from_millis(self.frame_delay),
+                self.step,
+            );
+            *VISUALIZER.lock().unwrap() = Some(visualizer);
+            ENABLED.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static VISUALIZER: Mutex<Option<Visualizer>> = Mutex::new(None);
+
+/// Whether frames are being drawn, for simulations that need to do extra work to draw them.
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Draws the frame `render` returns, if visualizing, and waits before the next one.
+pub fn frame(render: impl FnOnce() -> String) {
+    if !enabled() {
+        return;
+    }
+    let mut visualizer = VISUALIZER.lock().unwrap();
+    if let Some(v) = visualizer.as_mut() {
+        if v.draw(&render()) == Next::Stop {
+            *visualizer = None;
+            ENABLED.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Next {
+    Continue,
+    Stop,
+}
+
+struct Visualizer {
+    out: Box<dyn Write + Send>,
+    delay: Duration,
+    step: bool,
+    frames: usize,
+}
+
+impl Visualizer {
+    fn new(out: Box<dyn Write + Send>, delay: Duration, step: bool) -> Self {
+        Self {
+            out,
+            delay,
+            step,
+            frames: 0,
+        }
+    }
+
+    fn draw(&mut self, frame: &str) -> Next {
+        self.frames += 1;
+        // Clear the whole screen once, then redraw in place to avoid flicker
+        let clear = if self.frames == 1 { "\x1b[2J" } else { "" };
+        let drawn = write!(
+            self.out,
+            "{clear}\x1b[H{}\x1b[J\nframe {}\n",
+            frame.trim_end(),
+            self.frames
+        )
+        .and_then(|_| self.out.flush());
+        if drawn.is_err() {
+            return Next::Stop;
+        }
+
+        if self.step {
+            match read_key().as_deref() {
+                Some("q") => return Next::Stop,
+                Some("c") => self.step = false,
+                _ => (),
+            }
+        } else if !self.delay.is_zero() {
+            thread::sleep(self.delay);
+        }
+        Next::Continue
+    }
+}
+
+/// Reads a line from the terminal, which is still there when the puzzle input is piped to stdin.
+fn read_key() -> Option<String> {
+    let mut line = String::new();
+    match File::open("/dev/tty") {
+        Ok(tty) => BufReader::new(tty).read_line(&mut line).ok()?,
+        Err(_) => stdin().lock().read_line(&mut line).ok()?,
+    };
+    Some(line.trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_draw() {
+        let out = Output::default();
+        let mut visualizer = Visualizer::new(Box::new(out.clone()), Duration::ZERO, false);
+        assert_eq!(visualizer.draw("#.\n.#\n"), Next::Continue);
+        assert_eq!(visualizer.draw("..\n##"), Next::Continue);
+
+        let drawn = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            drawn,
+            "\x1b[2J\x1b[H#.\n.#\x1b[J\nframe 1\n\x1b[H..\n##\x1b[J\nframe 2\n"
+        );
+    }
+
+    #[test]
+    fn test_disabled_frames_are_not_rendered() {
+        frame(|| unreachable!("nothing is drawn without --visualize"));
+    }
+}
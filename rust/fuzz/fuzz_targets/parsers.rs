@@ -0,0 +1,20 @@
+//! Feeds arbitrary bytes to every day's input parser, which must answer with an error rather
+//! than panic, overflow or run out of stack. Run with:
+//!
+//! ```sh
+//! cargo +nightly fuzz run parsers
+//! ```
+//!
+//! `aoc/tests/parsers.rs` runs a quicker version of the same check with the other tests.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Puzzle inputs are read as UTF-8, so that's all the parsers ever see
+    let input = String::from_utf8_lossy(data);
+    for parse in aoc::solutions().filter_map(|d| d.parse) {
+        let _ = parse(&input);
+    }
+});
@@ -0,0 +1,331 @@
+use crate::{Direction, Pos};
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+/// A dense, rectangular grid of cells addressed by [`Pos`], with `(0, 0)` in the top left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    /// A row whose length differs from the first row (`line` is 1-based)
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCell {
+        pos: Pos,
+        c: char,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty grid"),
+            ParseError::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line} is {found} cells wide, expected {expected} cells"
+            ),
+            ParseError::InvalidCell { pos, c } => write!(
+                f,
+                "invalid cell {c:?} at line {}, column {}",
+                pos.y + 1,
+                pos.x + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Points the error at where it happened in `input`, the text the grid was parsed from.
+    pub fn locate(&self, input: &str) -> aoc_common::ParseError {
+        match *self {
+            ParseError::Empty => aoc_common::ParseError::at_end(input, "expected a grid"),
+            ParseError::Ragged {
+                line,
+                expected,
+                found,
+            } => aoc_common::ParseError::at_column(
+                input,
+                line - 1,
+                expected.min(found),
+                format!("row is {found} cells wide, expected {expected} cells"),
+            ),
+            ParseError::InvalidCell { pos, c } => aoc_common::ParseError::at_column(
+                input,
+                pos.y as usize,
+                pos.x as usize,
+                format!("invalid cell {c:?}"),
+            ),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses one row per line and one cell per character, converting characters with `f`. A
+    /// character `f` returns `None` for is reported as [`ParseError::InvalidCell`].
+    pub fn parse_with<F>(s: &str, mut f: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in s.trim_end_matches(['\r', '\n']).lines().enumerate() {
+            let row_start = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                let pos = Pos::new(x as i32, y as i32);
+                cells.push(f(c).ok_or(ParseError::InvalidCell { pos, c })?);
+            }
+            let found = cells.len() - row_start;
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(ParseError::Ragged {
+                        line: y + 1,
+                        expected,
+                        found,
+                    })
+                }
+                _ => (),
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(ParseError::Empty),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < self.width && (pos.y as usize) < self.height
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        if self.contains(pos) {
+            Some(pos.y as usize * self.width + pos.x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Every position on the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.width * self.height)
+            .map(move |idx| Pos::new((idx % width) as i32, (idx / width) as i32))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn find<F>(&self, mut f: F) -> Option<Pos>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().find_map(|(pos, v)| f(v).then_some(pos))
+    }
+
+    /// The orthogonal neighbours of `pos` that are on the grid.
+    pub fn neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.neighbours_4().filter(|p| self.contains(*p))
+    }
+
+    /// The orthogonal and diagonal neighbours of `pos` that are on the grid.
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.neighbours_8().filter(|p| self.contains(*p))
+    }
+
+    /// The cells from `from` (exclusive) in direction `dir` until the edge of the grid.
+    pub fn walk(&self, from: Pos, dir: Direction) -> impl Iterator<Item = (Pos, &T)> {
+        std::iter::successors(Some(from), move |p| Some(p.walk(dir)))
+            .skip(1)
+            .map_while(|p| Some((p, self.get(p)?)))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, Some)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, row) in self.cells.chunks(self.width).enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Draws the area from `min` to `max` (inclusive) one character per position, for sparse maps
+/// that don't live in a [`Grid`].
+pub fn render(min: Pos, max: Pos, cell: impl Fn(Pos) -> char) -> String {
+    let mut s = String::new();
+    for y in min.y..=max.y {
+        if y > min.y {
+            s.push('\n');
+        }
+        s.extend((min.x..=max.x).map(|x| cell(Pos::new(x, y))));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid: Grid<char> = "#..\n.#.\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 1)], '#');
+        assert_eq!(grid.get(Pos::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "#..\n.#.");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Grid<char>>(), Err(ParseError::Empty));
+        assert_eq!(
+            "...\n..\n".parse::<Grid<char>>(),
+            Err(ParseError::Ragged {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Grid::parse_with("12\n3x", |c| c.to_digit(10)),
+            Err(ParseError::InvalidCell {
+                pos: Pos::new(1, 1),
+                c: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn test_locate() {
+        let input = "12\n3x\n";
+        let err = Grid::parse_with(input, |c| c.to_digit(10))
+            .unwrap_err()
+            .locate(input);
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "3x"));
+
+        let input = "...\n..\n";
+        let err = input.parse::<Grid<char>>().unwrap_err().locate(input);
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn test_render() {
+        let s = render(Pos::new(-1, 0), Pos::new(1, 1), |p| {
+            if p.x == p.y {
+                '#'
+            } else {
+                '.'
+            }
+        });
+        assert_eq!(s, ".#.\n..#");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbours_4(Pos::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours_8(Pos::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours_8(Pos::new(1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_walk() {
+        let grid = Grid::parse_with("123\n456\n789", |c| c.to_digit(10)).unwrap();
+        let walked = grid
+            .walk(Pos::new(1, 2), Direction::Up)
+            .map(|(_, v)| *v)
+            .collect::<Vec<_>>();
+        assert_eq!(walked, vec![5, 2]);
+    }
+}
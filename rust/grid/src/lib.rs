@@ -0,0 +1,5 @@
+mod grid;
+mod pos;
+
+pub use grid::{render, Grid, ParseError};
+pub use pos::{Direction, Pos, OFFSETS_8};
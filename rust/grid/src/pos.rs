@@ -0,0 +1,137 @@
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+/// A position on a grid. `y` grows downwards, so `Direction::Up` decreases it.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Default)]
+pub struct Pos {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Offsets to the eight neighbours of a position, row by row from the top left.
+#[rustfmt::skip]
+pub const OFFSETS_8: [Pos; 8] = [
+    Pos::new(-1, -1), Pos::new(0, -1), Pos::new(1, -1),
+    Pos::new(-1,  0),                  Pos::new(1,  0),
+    Pos::new(-1,  1), Pos::new(0,  1), Pos::new(1,  1),
+];
+
+impl Pos {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn walk(&self, dir: Direction) -> Self {
+        *self + dir.offset()
+    }
+
+    pub fn manhattan_dist(&self, other: &Pos) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The four orthogonal neighbours, in the order of [`Direction::ALL`].
+    pub fn neighbours_4(self) -> impl Iterator<Item = Pos> {
+        Direction::ALL.into_iter().map(move |dir| self.walk(dir))
+    }
+
+    /// All eight neighbours, including the diagonals, in the order of [`OFFSETS_8`].
+    pub fn neighbours_8(self) -> impl Iterator<Item = Pos> {
+        OFFSETS_8.into_iter().map(move |offset| self + offset)
+    }
+}
+
+impl From<(i32, i32)> for Pos {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Add for Pos {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Pos {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl AddAssign for Pos {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Pos {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Pos {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl SubAssign for Pos {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Pos {
+    type Output = Self;
+    fn mul(self, rhs: i32) -> Self::Output {
+        Pos {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting with `Up`.
+    pub const ALL: [Direction; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn offset(&self) -> Pos {
+        match self {
+            Self::Up => Pos::new(0, -1),
+            Self::Right => Pos::new(1, 0),
+            Self::Down => Pos::new(0, 1),
+            Self::Left => Pos::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    pub fn turn_left(&self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Right => Self::Up,
+            Self::Down => Self::Right,
+            Self::Left => Self::Down,
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Right => Self::Left,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! Prints the disassembly of the Intcode program in the file given, or on stdin.
+
+use std::process::ExitCode;
+
+fn disassemble() -> anyhow::Result<()> {
+    let input = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => aoc_common::read_stdin()?,
+    };
+    for instruction in aoc_intcode::disassemble(&aoc_intcode::parse(&input)?) {
+        println!("{instruction}");
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    aoc_common::report(disassemble())
+}
@@ -0,0 +1,108 @@
+use crate::{decode, Mode, Opcode};
+use std::fmt;
+
+/// An instruction in a disassembled program, or a word that doesn't decode as one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    Op {
+        addr: usize,
+        opcode: Opcode,
+        params: Vec<(Mode, i64)>,
+    },
+    Data {
+        addr: usize,
+        value: i64,
+    },
+}
+
+/// Disassembles `program` from the start, instruction after instruction. Anything that doesn't
+/// decode, or runs past the end of the program, is listed as data. Data that happens to decode,
+/// like the memory past a program's final `halt`, is listed as instructions all the same.
+pub fn disassemble(program: &[i64]) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut addr = 0;
+    while addr < program.len() {
+        let value = program[addr];
+        let instruction = match decode(value) {
+            Ok((opcode, modes)) if addr + opcode.params() < program.len() => Instruction::Op {
+                addr,
+                opcode,
+                params: (0..opcode.params())
+                    .map(|n| (modes[n], program[addr + 1 + n]))
+                    .collect(),
+            },
+            _ => Instruction::Data { addr, value },
+        };
+        addr += match &instruction {
+            Instruction::Op { params, .. } => 1 + params.len(),
+            Instruction::Data { .. } => 1,
+        };
+        instructions.push(instruction);
+    }
+    instructions
+}
+
+fn fmt_param(f: &mut fmt::Formatter<'_>, (mode, value): (Mode, i64)) -> fmt::Result {
+    match mode {
+        Mode::Position => write!(f, "[{value}]"),
+        Mode::Immediate => write!(f, "{value}"),
+        Mode::Relative => write!(f, "[rb{value:+}]"),
+    }
+}
+
+/// Lists the instruction as its address, mnemonic and parameters, like `  12: add [9], 3 -> [rb+1]`.
+/// Addresses are in brackets, relative to the relative base `rb` for relative mode.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Data { addr, value } => write!(f, "{addr:>4}: data {value}"),
+            Instruction::Op {
+                addr,
+                opcode,
+                params,
+            } => {
+                write!(f, "{addr:>4}: {}", opcode.mnemonic())?;
+                let (read, written) = match opcode.writes() {
+                    true => params.split_at(params.len() - 1),
+                    false => (&params[..], &[][..]),
+                };
+                for (n, param) in read.iter().enumerate() {
+                    f.write_str(if n == 0 { " " } else { ", " })?;
+                    fmt_param(f, *param)?;
+                }
+                for param in written {
+                    f.write_str(if read.is_empty() { " " } else { " -> " })?;
+                    fmt_param(f, *param)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disassemble() {
+        let program = [3, 9, 1008, 9, 8, 9, 204, -1, 99, 0, 42, 1101, 1];
+        let listing: Vec<String> = disassemble(&program)
+            .iter()
+            .map(|i| i.to_string())
+            .collect();
+        assert_eq!(
+            listing,
+            [
+                "   0: in [9]",
+                "   2: eq [9], 8 -> [9]",
+                "   6: out [rb-1]",
+                "   8: halt",
+                "   9: data 0",
+                "  10: data 42",
+                "  11: data 1101",
+                "  12: data 1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! The Intcode computer the 2019 puzzles keep building on.
+
+mod disassemble;
+mod machine;
+mod memory;
+
+use aoc_common::ParseError;
+
+pub use disassemble::{disassemble, Instruction};
+pub use machine::{decode, Machine, Mode, Opcode, Pipeline, Stop};
+pub use memory::Memory;
+
+/// Parses a comma separated Intcode program.
+pub fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    let program = input.trim_end();
+    if program.is_empty() {
+        return Err(ParseError::at_end(input, "expected an Intcode program"));
+    }
+    program
+        .split(',')
+        .map(|word| {
+            let trimmed = word.trim();
+            trimmed
+                .parse()
+                .map_err(|err| ParseError::at(input, trimmed, err))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("1,0,-3, 99\n").unwrap(), [1, 0, -3, 99]);
+        let err = parse("1,0,x,99").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+        assert!(parse("1,,99").is_err());
+        assert!(parse("\n").is_err());
+    }
+}
@@ -0,0 +1,445 @@
+use crate::Memory;
+use anyhow::{anyhow, bail, Context};
+use std::{
+    collections::VecDeque,
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Add,
+    Mul,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equals,
+    AdjustBase,
+    Halt,
+}
+
+impl Opcode {
+    pub fn from_code(code: i64) -> Option<Self> {
+        let opcode = match code {
+            1 => Opcode::Add,
+            2 => Opcode::Mul,
+            3 => Opcode::Input,
+            4 => Opcode::Output,
+            5 => Opcode::JumpIfTrue,
+            6 => Opcode::JumpIfFalse,
+            7 => Opcode::LessThan,
+            8 => Opcode::Equals,
+            9 => Opcode::AdjustBase,
+            99 => Opcode::Halt,
+            _ => return None,
+        };
+        Some(opcode)
+    }
+
+    /// How many parameters follow the instruction word.
+    pub fn params(self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => 3,
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => 2,
+            Opcode::Input | Opcode::Output | Opcode::AdjustBase => 1,
+            Opcode::Halt => 0,
+        }
+    }
+
+    /// Whether the last parameter is the address the instruction writes to.
+    pub fn writes(self) -> bool {
+        matches!(
+            self,
+            Opcode::Add | Opcode::Mul | Opcode::Input | Opcode::LessThan | Opcode::Equals
+        )
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Add => "add",
+            Opcode::Mul => "mul",
+            Opcode::Input => "in",
+            Opcode::Output => "out",
+            Opcode::JumpIfTrue => "jnz",
+            Opcode::JumpIfFalse => "jz",
+            Opcode::LessThan => "lt",
+            Opcode::Equals => "eq",
+            Opcode::AdjustBase => "arb",
+            Opcode::Halt => "halt",
+        }
+    }
+}
+
+/// How a parameter is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The parameter is an address
+    Position,
+    /// The parameter is the value itself
+    Immediate,
+    /// The parameter is an address relative to the machine's relative base
+    Relative,
+}
+
+/// Splits an instruction word into its opcode and the modes of its parameters. Parameters the
+/// opcode doesn't take are left in [`Mode::Position`].
+pub fn decode(word: i64) -> anyhow::Result<(Opcode, [Mode; 3])> {
+    let opcode = Opcode::from_code(word % 100).ok_or_else(|| anyhow!("invalid opcode {word}"))?;
+    let mut modes = [Mode::Position; 3];
+    let mut digits = word / 100;
+    for mode in modes.iter_mut().take(opcode.params()) {
+        *mode = match digits % 10 {
+            0 => Mode::Position,
+            1 => Mode::Immediate,
+            2 => Mode::Relative,
+            n => bail!("invalid parameter mode {n} in {word}"),
+        };
+        digits /= 10;
+    }
+    Ok((opcode, modes))
+}
+
+fn checked(value: Option<i64>) -> anyhow::Result<i64> {
+    value.ok_or_else(|| anyhow!("arithmetic overflow"))
+}
+
+/// Why a machine stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Output(i64),
+    /// The machine is at an input instruction and has no input queued
+    NeedsInput,
+    Halted,
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    memory: Memory,
+    pc: i64,
+    /// The relative base
+    base: i64,
+    inputs: VecDeque<i64>,
+    halted: bool,
+}
+
+impl Machine {
+    pub fn new(program: &[i64]) -> Self {
+        Self {
+            memory: Memory::new(program),
+            pc: 0,
+            base: 0,
+            inputs: VecDeque::new(),
+            halted: false,
+        }
+    }
+
+    /// Queues `inputs` for the machine to read.
+    pub fn with_inputs(mut self, inputs: impl IntoIterator<Item = i64>) -> Self {
+        self.inputs.extend(inputs);
+        self
+    }
+
+    pub fn push_input(&mut self, value: i64) {
+        self.inputs.push_back(value);
+    }
+
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    pub fn memory_mut(&mut self) -> &mut Memory {
+        &mut self.memory
+    }
+
+    pub fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// The address of parameter `n` of the current instruction.
+    fn address(&self, n: usize, mode: Mode) -> anyhow::Result<i64> {
+        let param = self.pc + 1 + n as i64;
+        match mode {
+            Mode::Position => self.memory.get(param),
+            Mode::Immediate => Ok(param),
+            Mode::Relative => checked(self.base.checked_add(self.memory.get(param)?)),
+        }
+    }
+
+    fn read(&self, n: usize, modes: [Mode; 3]) -> anyhow::Result<i64> {
+        self.memory.get(self.address(n, modes[n])?)
+    }
+
+    fn write(&mut self, n: usize, modes: [Mode; 3], value: i64) -> anyhow::Result<()> {
+        if modes[n] == Mode::Immediate {
+            bail!("parameter {} is written to, but in immediate mode", n + 1);
+        }
+        self.memory.set(self.address(n, modes[n])?, value)
+    }
+
+    /// Executes a single instruction, returning why the machine stopped if it did. An input
+    /// instruction without input to read is left to be executed again once there is some.
+    pub fn step(&mut self) -> anyhow::Result<Option<Stop>> {
+        if self.halted {
+            return Ok(Some(Stop::Halted));
+        }
+        self.execute()
+            .with_context(|| format!("at address {}", self.pc))
+    }
+
+    fn execute(&mut self) -> anyhow::Result<Option<Stop>> {
+        let (opcode, modes) = decode(self.memory.get(self.pc)?)?;
+        let mut next = self.pc + 1 + opcode.params() as i64;
+        let mut stop = None;
+        match opcode {
+            Opcode::Add => {
+                let value = checked(self.read(0, modes)?.checked_add(self.read(1, modes)?))?;
+                self.write(2, modes, value)?
+            }
+            Opcode::Mul => {
+                let value = checked(self.read(0, modes)?.checked_mul(self.read(1, modes)?))?;
+                self.write(2, modes, value)?
+            }
+            Opcode::Input => match self.inputs.pop_front() {
+                Some(value) => self.write(0, modes, value)?,
+                None => return Ok(Some(Stop::NeedsInput)),
+            },
+            Opcode::Output => stop = Some(Stop::Output(self.read(0, modes)?)),
+            Opcode::JumpIfTrue => {
+                if self.read(0, modes)? != 0 {
+                    next = self.read(1, modes)?;
+                }
+            }
+            Opcode::JumpIfFalse => {
+                if self.read(0, modes)? == 0 {
+                    next = self.read(1, modes)?;
+                }
+            }
+            Opcode::LessThan => {
+                let value = self.read(0, modes)? < self.read(1, modes)?;
+                self.write(2, modes, value.into())?
+            }
+            Opcode::Equals => {
+                let value = self.read(0, modes)? == self.read(1, modes)?;
+                self.write(2, modes, value.into())?
+            }
+            Opcode::AdjustBase => self.base = checked(self.base.checked_add(self.read(0, modes)?))?,
+            Opcode::Halt => {
+                self.halted = true;
+                return Ok(Some(Stop::Halted));
+            }
+        }
+        self.pc = next;
+        Ok(stop)
+    }
+
+    /// Runs until the machine outputs something, needs input it doesn't have, or halts.
+    pub fn run_until_output(&mut self) -> anyhow::Result<Stop> {
+        loop {
+            if let Some(stop) = self.step()? {
+                return Ok(stop);
+            }
+        }
+    }
+
+    /// Runs until the machine needs input it doesn't have, or halts, returning what it output on
+    /// the way.
+    pub fn run_until_input(&mut self) -> anyhow::Result<Vec<i64>> {
+        let mut outputs = Vec::new();
+        while let Stop::Output(value) = self.run_until_output()? {
+            outputs.push(value);
+        }
+        Ok(outputs)
+    }
+
+    /// Runs the machine until it halts, returning its output. Running out of input is an error.
+    pub fn run(&mut self) -> anyhow::Result<Vec<i64>> {
+        let outputs = self.run_until_input()?;
+        if !self.halted {
+            bail!("ran out of input at address {}", self.pc);
+        }
+        Ok(outputs)
+    }
+
+    /// Runs the machine on a thread of its own. It reads from `inputs` once its queued inputs run
+    /// out and sends its output to `outputs`, and hands itself back when it halts.
+    pub fn spawn(
+        mut self,
+        inputs: Receiver<i64>,
+        outputs: Sender<i64>,
+    ) -> JoinHandle<anyhow::Result<Machine>> {
+        thread::spawn(move || loop {
+            match self.run_until_output()? {
+                // Output nobody listens for any more, like the feedback to a machine that has
+                // already halted, is dropped
+                Stop::Output(value) => _ = outputs.send(value),
+                Stop::NeedsInput => {
+                    let value = inputs.recv().map_err(|_| {
+                        anyhow!("input closed while waiting for it at address {}", self.pc)
+                    })?;
+                    self.push_input(value);
+                }
+                Stop::Halted => return Ok(self),
+            }
+        })
+    }
+}
+
+/// Machines running on threads of their own, each one's output connected to the next one's input.
+pub struct Pipeline {
+    /// The input of the first machine
+    pub input: Sender<i64>,
+    /// The output of the last machine
+    pub output: Receiver<i64>,
+    handles: Vec<JoinHandle<anyhow::Result<Machine>>>,
+}
+
+impl Pipeline {
+    pub fn new(machines: impl IntoIterator<Item = Machine>) -> Self {
+        let (input, mut output) = mpsc::channel();
+        let handles = machines
+            .into_iter()
+            .map(|machine| {
+                let (sender, receiver) = mpsc::channel();
+                machine.spawn(std::mem::replace(&mut output, receiver), sender)
+            })
+            .collect();
+        Self {
+            input,
+            output,
+            handles,
+        }
+    }
+
+    /// Feeds the output of the last machine back into the first one until they've all halted,
+    /// returning everything the last machine output.
+    pub fn feedback(self) -> anyhow::Result<Vec<i64>> {
+        let mut outputs = Vec::new();
+        for value in &self.output {
+            outputs.push(value);
+            _ = self.input.send(value);
+        }
+        self.join()?;
+        Ok(outputs)
+    }
+
+    /// Waits for every machine to halt, and returns them in order. Machines still waiting for the
+    /// pipeline's input fail, as that's closed first.
+    pub fn join(self) -> anyhow::Result<Vec<Machine>> {
+        let Self {
+            input,
+            output,
+            handles,
+        } = self;
+        drop((input, output));
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(program: &[i64], inputs: &[i64]) -> Vec<i64> {
+        let mut machine = Machine::new(program).with_inputs(inputs.iter().copied());
+        machine.run().unwrap()
+    }
+
+    #[test]
+    fn test_memory_after_run() {
+        let mut machine = Machine::new(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]);
+        assert_eq!(machine.run().unwrap(), []);
+        assert_eq!(machine.memory().get(0).unwrap(), 3500);
+        assert_eq!(machine.pc(), 8);
+
+        let mut machine = Machine::new(&[1, 1, 1, 4, 99, 5, 6, 0, 99]);
+        machine.run().unwrap();
+        assert_eq!(machine.memory().cells(), [30, 1, 1, 4, 2, 5, 6, 0, 99]);
+    }
+
+    #[test]
+    fn test_modes_and_comparisons() {
+        // Outputs whether the input equals 8, with position and immediate modes
+        let position = [3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+        let immediate = [3, 3, 1108, -1, 8, 3, 4, 3, 99];
+        for program in [&position[..], &immediate] {
+            assert_eq!(run(program, &[8]), [1]);
+            assert_eq!(run(program, &[7]), [0]);
+        }
+        // Outputs whether the input is non-zero, jumping in position mode
+        let jumps = [3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9];
+        assert_eq!(run(&jumps, &[0]), [0]);
+        assert_eq!(run(&jumps, &[5]), [1]);
+    }
+
+    #[test]
+    fn test_relative_base_and_large_memory() {
+        let quine = [
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        assert_eq!(run(&quine, &[]), quine);
+        assert_eq!(
+            run(&[1102, 34915192, 34915192, 7, 4, 7, 99, 0], &[]),
+            [1219070632396864]
+        );
+        assert_eq!(run(&[104, 1125899906842624, 99], &[]), [1125899906842624]);
+    }
+
+    #[test]
+    fn test_run_until_input() {
+        // Echoes its input doubled, forever
+        let mut machine = Machine::new(&[3, 9, 1002, 9, 2, 9, 4, 9, 1105, 1, 0]);
+        assert_eq!(machine.run_until_input().unwrap(), []);
+        machine.push_input(3);
+        machine.push_input(4);
+        assert_eq!(machine.run_until_input().unwrap(), [6, 8]);
+        assert!(!machine.is_halted());
+        assert!(machine.run().is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = Machine::new(&[1, 0, 0, 0, 42]).run().unwrap_err();
+        assert_eq!(format!("{err:#}"), "at address 4: invalid opcode 42");
+        assert!(Machine::new(&[1101, 1, 1, 0, 99]).run().is_ok());
+        assert!(Machine::new(&[11101, 1, 1, 0, 99]).run().is_err());
+        assert!(Machine::new(&[301, 1, 1, 0, 99]).run().is_err());
+        assert!(Machine::new(&[4, -1, 99]).run().is_err());
+        assert!(Machine::new(&[1102, i64::MAX, 2, 0, 99]).run().is_err());
+    }
+
+    #[test]
+    fn test_pipeline() {
+        // Reads a number and outputs it plus one
+        let increment = [3, 0, 1001, 0, 1, 0, 4, 0, 99];
+        let pipeline = Pipeline::new((0..3).map(|_| Machine::new(&increment)));
+        pipeline.input.send(5).unwrap();
+        assert_eq!(pipeline.output.recv().unwrap(), 8);
+        assert_eq!(pipeline.join().unwrap().len(), 3);
+
+        // Adds its first input to the next two and outputs the sums, so two of them in a loop
+        // keep adding their first inputs to the value going round
+        let adder = [
+            3, 20, 3, 21, 1, 20, 21, 21, 4, 21, 3, 21, 1, 20, 21, 21, 4, 21, 99,
+        ];
+        let machines = [10, 100].map(|n| Machine::new(&adder).with_inputs([n]));
+        let pipeline = Pipeline::new(machines);
+        pipeline.input.send(1).unwrap();
+        assert_eq!(pipeline.feedback().unwrap(), [111, 221]);
+
+        let pipeline = Pipeline::new([Machine::new(&increment)]);
+        assert!(pipeline.join().is_err());
+    }
+}
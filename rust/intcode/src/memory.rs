@@ -0,0 +1,65 @@
+use anyhow::anyhow;
+
+/// Programs can't address more than this many cells, so a stray write to a huge address fails
+/// instead of trying to allocate it.
+const MAX_SIZE: usize = 1 << 24;
+
+/// An Intcode machine's memory. It starts out holding the program, reads past the end of what's
+/// been written as zero, and grows as far as the writes reach.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Memory {
+    cells: Vec<i64>,
+}
+
+fn address(addr: i64) -> anyhow::Result<usize> {
+    usize::try_from(addr)
+        .ok()
+        .filter(|addr| *addr < MAX_SIZE)
+        .ok_or_else(|| anyhow!("address {addr} is out of range"))
+}
+
+impl Memory {
+    pub fn new(program: &[i64]) -> Self {
+        Self {
+            cells: program.to_vec(),
+        }
+    }
+
+    pub fn get(&self, addr: i64) -> anyhow::Result<i64> {
+        let addr = address(addr)?;
+        Ok(self.cells.get(addr).copied().unwrap_or(0))
+    }
+
+    pub fn set(&mut self, addr: i64, value: i64) -> anyhow::Result<()> {
+        let addr = address(addr)?;
+        if addr >= self.cells.len() {
+            self.cells.resize(addr + 1, 0);
+        }
+        self.cells[addr] = value;
+        Ok(())
+    }
+
+    /// The memory up to the furthest cell written so far.
+    pub fn cells(&self) -> &[i64] {
+        &self.cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grows_on_demand() {
+        let mut memory = Memory::new(&[1, 2]);
+        assert_eq!(memory.get(1000).unwrap(), 0);
+        assert_eq!(memory.cells().len(), 2);
+
+        memory.set(5, 7).unwrap();
+        assert_eq!(memory.cells(), [1, 2, 0, 0, 0, 7]);
+        assert_eq!(memory.get(5).unwrap(), 7);
+
+        assert!(memory.get(-1).is_err());
+        assert!(memory.set(i64::MAX, 1).is_err());
+    }
+}